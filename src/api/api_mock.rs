@@ -1,43 +1,96 @@
 use super::api_trait::API;
-use std::fs::File;
-use std::io::prelude::*;
-use std::path::Path;
+use crate::error::{Error, Result};
+use crate::model::TimeWindow;
+use crate::utils::TestHelper;
 
 /// The mock API is only used for tests. It implements the shared API trait so the mock can be used
 /// interchangeably with a concrete API like the AppCenter API.
 pub struct MockAPI {
-    crashes: Option<String>,
+    crashes: Option<Vec<String>>,
+    versions: Option<String>,
+    operating_systems: Option<String>,
+    statistics: Option<(String, String, String)>,
+    stacktrace: Option<String>,
 }
 
 impl MockAPI {
-    pub fn with_crashes_json(json: String) -> impl API {
+    pub fn with_crashes_json(json: String) -> MockAPI {
+        MockAPI::with_crash_pages(vec![json])
+    }
+
+    /// Serves the given pages of error groups as if the continuation links were followed.
+    pub fn with_crash_pages(pages: Vec<String>) -> MockAPI {
         MockAPI {
-            crashes: Some(json),
+            crashes: Some(pages),
+            versions: None,
+            operating_systems: None,
+            statistics: None,
+            stacktrace: None,
         }
     }
 
-    pub fn with_two_crashes() -> impl API {
-        let path = Path::new("src/json_parsing/test_fixtures/two_crashes.json");
-        let mut file = File::open(&path).expect("Unable to open test fixture");
-        let mut crashes_json = String::new();
-        file.read_to_string(&mut crashes_json)
-            .expect("Failed to read fixture file into string");
+    pub fn with_two_crashes() -> MockAPI {
+        MockAPI::with_crashes_json(TestHelper::fixture(
+            "src/json_parsing/test_fixtures/two_crashes.json",
+        ))
+    }
+
+    /// A mock serving five crashes split into two pages.
+    pub fn with_paginated_crashes() -> MockAPI {
+        MockAPI::with_crash_pages(vec![
+            TestHelper::fixture("src/json_parsing/test_fixtures/paginated_crashes_page_1.json"),
+            TestHelper::fixture("src/json_parsing/test_fixtures/paginated_crashes_page_2.json"),
+        ])
+    }
+
+    /// Serves the given list of versions, e.g. to find the latest version.
+    pub fn and_versions_json(mut self, json: String) -> MockAPI {
+        self.versions = Some(json);
+        self
+    }
+
+    /// Serves the crash-free device percentages, session counts and crash counts of the
+    /// `statistics` test fixtures.
+    pub fn and_statistics(mut self) -> MockAPI {
+        self.statistics = Some((
+            TestHelper::fixture("src/json_parsing/test_fixtures/crash_free_devices.json"),
+            TestHelper::fixture("src/json_parsing/test_fixtures/session_counts.json"),
+            TestHelper::fixture("src/json_parsing/test_fixtures/crash_counts.json"),
+        ));
+        self
+    }
 
-        MockAPI::with_crashes_json(crashes_json)
+    /// Serves the given operating system details for every error group.
+    pub fn and_operating_systems_json(mut self, json: String) -> MockAPI {
+        self.operating_systems = Some(json);
+        self
+    }
+
+    /// Serves the stack trace of the `stacktrace.json` test fixture for every error group.
+    pub fn and_stacktrace(mut self) -> MockAPI {
+        self.stacktrace = Some(TestHelper::fixture(
+            "src/json_parsing/test_fixtures/stacktrace.json",
+        ));
+        self
     }
 }
 
 impl API for MockAPI {
     fn new(_token: String) -> Self {
-        MockAPI { crashes: None }
+        MockAPI {
+            crashes: None,
+            versions: None,
+            operating_systems: None,
+            statistics: None,
+            stacktrace: None,
+        }
     }
 
-    fn latest_version(
-        &self,
-        _organization: String,
-        _application: String,
-    ) -> Result<String, &'static str> {
-        Ok("1.2.3".to_string())
+    fn latest_version(&self, _organization: String, _application: String) -> Result<String> {
+        match self.versions.clone() {
+            Some(json) => Ok(json),
+            _ => Err(Error::NotFound),
+        }
     }
 
     fn crashes_json(
@@ -45,10 +98,13 @@ impl API for MockAPI {
         _organization: String,
         _application: String,
         _version: String,
-    ) -> Result<String, &'static str> {
+        _build: Option<String>,
+        _time_window: Option<TimeWindow>,
+        _limit: Option<usize>,
+    ) -> Result<Vec<String>> {
         match self.crashes.clone() {
-            Some(json) => Ok(json),
-            _ => Err("Mock has no crashes"),
+            Some(pages) => Ok(pages),
+            _ => Err(Error::NotFound),
         }
     }
 
@@ -57,10 +113,61 @@ impl API for MockAPI {
         _organization: &str,
         _application: &str,
         _error_group_id: &str,
-    ) -> Result<String, &'static str> {
-        match self.crashes.clone() {
+    ) -> Result<String> {
+        match self.operating_systems.clone() {
+            Some(json) => Ok(json),
+            _ => Err(Error::NotFound),
+        }
+    }
+
+    fn stacktrace_json(
+        &self,
+        _organization: &str,
+        _application: &str,
+        _error_group_id: &str,
+    ) -> Result<String> {
+        match self.stacktrace.clone() {
             Some(json) => Ok(json),
-            _ => Err("Something went wrong. There are no crashes in the json."),
+            _ => Err(Error::NotFound),
+        }
+    }
+
+    fn crash_free_devices_json(
+        &self,
+        _organization: &str,
+        _application: &str,
+        _version: &str,
+        _time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        match &self.statistics {
+            Some((json, _, _)) => Ok(json.clone()),
+            _ => Err(Error::NotFound),
+        }
+    }
+
+    fn session_counts_json(
+        &self,
+        _organization: &str,
+        _application: &str,
+        _version: &str,
+        _time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        match &self.statistics {
+            Some((_, json, _)) => Ok(json.clone()),
+            _ => Err(Error::NotFound),
+        }
+    }
+
+    fn crash_counts_json(
+        &self,
+        _organization: &str,
+        _application: &str,
+        _version: &str,
+        _time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        match &self.statistics {
+            Some((_, _, json)) => Ok(json.clone()),
+            _ => Err(Error::NotFound),
         }
     }
 }
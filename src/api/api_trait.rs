@@ -1,4 +1,9 @@
-pub trait API {
+use crate::error::Result;
+use crate::model::TimeWindow;
+
+/// An API providing crash data. Implementations must be shareable between threads, as details of
+/// error groups are fetched concurrently.
+pub trait API: Send + Sync {
     /// Create a new API
     ///
     /// ```
@@ -8,27 +13,67 @@ pub trait API {
     /// let api = AppCenter::new("abc".to_string());
     /// assert_eq!(api.token, "abc");
     /// ```
-    fn new(token: String) -> Self;
+    fn new(token: String) -> Self
+    where
+        Self: Sized;
 
     /// Get the latest available version
-    fn latest_version(
-        &self,
-        organization: String,
-        application: String,
-    ) -> Result<String, &'static str>;
+    fn latest_version(&self, organization: String, application: String) -> Result<String>;
 
-    /// Get the json for crashes
+    /// Get the json pages for crashes of a version, or only of one of its builds if a build is
+    /// given. Continuation links are followed until all error groups are fetched, or at least
+    /// `limit` of them if a limit is given.
     fn crashes_json(
         &self,
         organization: String,
         application: String,
         version: String,
-    ) -> Result<String, &'static str>;
+        build: Option<String>,
+        time_window: Option<TimeWindow>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>>;
 
     fn os_versions(
         &self,
         organization: &str,
         application: &str,
         error_group_id: &str,
-    ) -> Result<String, &'static str>;
+    ) -> Result<String>;
+
+    /// Get the json of the representative stack trace of an error group, with its threads,
+    /// frames and exception.
+    fn stacktrace_json(
+        &self,
+        organization: &str,
+        application: &str,
+        error_group_id: &str,
+    ) -> Result<String>;
+
+    /// Get the json of the average share of devices without crashes in the version. Without a
+    /// time window AppCenter's default period of 30 days is used.
+    fn crash_free_devices_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String>;
+
+    /// Get the json of the daily session counts of the version.
+    fn session_counts_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String>;
+
+    /// Get the json of the daily crash counts of the version.
+    fn crash_counts_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String>;
 }
@@ -1,13 +1,32 @@
 extern crate reqwest;
 
 use super::api_trait::API;
-use reqwest::Client;
+use super::RetryPolicy;
+use crate::error::{Error, Result};
+use crate::json_parsing::crash_parsing;
+use crate::model::TimeWindow;
+use chrono::{TimeDelta, Utc};
+use reqwest::header::RETRY_AFTER;
+use reqwest::{Client, StatusCode};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
 use std::io::Read;
+use std::time::Duration;
 
-static API_VERSION: &str = "v0.1";
+/// The base url of the public AppCenter API.
+pub static DEFAULT_BASE_URL: &str = "https://api.appcenter.ms";
+
+/// The AppCenter API version all endpoints are requested with.
+pub static DEFAULT_API_VERSION: &str = "v0.1";
+
+/// The amount of error groups requested per page.
+static ERROR_GROUPS_PAGE_SIZE: usize = 30;
 
 pub struct AppCenter {
     pub token: String,
+    base_url: String,
+    api_version: String,
+    retry_policy: Option<RetryPolicy>,
     client: Client,
 }
 
@@ -15,36 +34,16 @@ impl API for AppCenter {
     fn new(token: String) -> Self {
         AppCenter {
             token,
+            base_url: DEFAULT_BASE_URL.to_string(),
+            api_version: DEFAULT_API_VERSION.to_string(),
+            retry_policy: None,
             client: Client::new(),
         }
     }
 
-    fn latest_version(
-        &self,
-        organization: String,
-        application: String,
-    ) -> Result<String, &'static str> {
-        let url = format!(
-            "https://api.appcenter.ms/{}/apps/{}/{}/recent_releases",
-            API_VERSION, organization, application
-        );
-        let response = self
-            .client
-            .get(&url)
-            .header("X-API-Token", self.token.clone())
-            .header("accept", "application/json")
-            .send();
-
-        match response {
-            Ok(mut response) => {
-                let mut json = String::new();
-                match response.read_to_string(&mut json) {
-                    Ok(_j) => Ok(json),
-                    Err(_e) => Err("Failed to read response from API"),
-                }
-            }
-            Err(_e) => Err("Failed to fetch latest versions json"),
-        }
+    fn latest_version(&self, organization: String, application: String) -> Result<String> {
+        let url = self.url(&organization, &application, "recent_releases");
+        self.get(&url)
     }
 
     fn crashes_json(
@@ -52,24 +51,50 @@ impl API for AppCenter {
         organization: String,
         application: String,
         version: String,
-    ) -> Result<String, &'static str> {
-        let url = format!("https://api.appcenter.ms/{}/apps/{}/{}/errors/errorGroups?version={}&%24orderby=count%20desc&%24top=20", API_VERSION, organization, application, version);
-        let response = self
-            .client
-            .get(&url)
-            .header("X-API-Token", self.token.clone())
-            .header("accept", "application/json")
-            .send();
-        match response {
-            Ok(mut response) => {
-                let mut json = String::new();
-                match response.read_to_string(&mut json) {
-                    Ok(_j) => Ok(json),
-                    Err(e) => panic!("Failed to read response from API. Error: {}", e),
+        build: Option<String>,
+        time_window: Option<TimeWindow>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>> {
+        let page_size = limit.map_or(ERROR_GROUPS_PAGE_SIZE, |limit| {
+            limit.min(ERROR_GROUPS_PAGE_SIZE)
+        });
+        let build_filter = build.map_or(String::new(), |build| {
+            format!("&app_build={}", percent_encode(&build))
+        });
+        let time_filter =
+            time_window.map_or(String::new(), |window| format!("&{}", window.query()));
+        let url = self.url(
+            &organization,
+            &application,
+            &format!(
+                "errors/errorGroups?version={}{}{}&%24orderby=count%20desc&%24top={}",
+                percent_encode(&version),
+                build_filter,
+                time_filter,
+                page_size
+            ),
+        );
+        let mut pages = Vec::new();
+        let mut fetched_error_groups = 0;
+        let mut next_url = Some(url.clone());
+
+        while let Some(page_url) = next_url {
+            let json = self.get(&page_url)?;
+            let crash_list = crash_parsing::crash_list_from_json(json.as_str())?;
+            fetched_error_groups += crash_list.crashes.len();
+            pages.push(json);
+
+            let limit_reached = limit.is_some_and(|limit| fetched_error_groups >= limit);
+            next_url = match crash_list.next_link {
+                Some(next_link) if !limit_reached && !crash_list.crashes.is_empty() => {
+                    continuation_token(&next_link)
+                        .map(|token| format!("{}&%24token={}", url, token))
                 }
-            }
-            Err(e) => panic!("Failed to fetch error groups json. Error: {}", e),
+                _ => None,
+            };
         }
+
+        Ok(pages)
     }
 
     fn os_versions(
@@ -77,26 +102,269 @@ impl API for AppCenter {
         organization: &str,
         application: &str,
         error_group_id: &str,
-    ) -> Result<String, &'static str> {
-        let url = format!(
-            "https://api.appcenter.ms/{}/apps/{}/{}/errors/errorGroups/{}/operatingSystems",
-            API_VERSION, organization, application, error_group_id
+    ) -> Result<String> {
+        let url = self.url(
+            organization,
+            application,
+            &format!("errors/errorGroups/{}/operatingSystems", error_group_id),
+        );
+        self.get(&url)
+    }
+
+    fn stacktrace_json(
+        &self,
+        organization: &str,
+        application: &str,
+        error_group_id: &str,
+    ) -> Result<String> {
+        let url = self.url(
+            organization,
+            application,
+            &format!("errors/errorGroups/{}/stacktrace", error_group_id),
+        );
+        self.get(&url)
+    }
+
+    fn crash_free_devices_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let url = self.url(
+            organization,
+            application,
+            &format!(
+                "errors/errorfreeDevicePercentages?version={}&{}",
+                percent_encode(version),
+                time_query(time_window)
+            ),
         );
-        let response = self
+        self.get(&url)
+    }
+
+    fn session_counts_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let url = self.url(
+            organization,
+            application,
+            &format!(
+                "analytics/session_counts?versions={}&interval=P1D&{}",
+                percent_encode(version),
+                time_query(time_window)
+            ),
+        );
+        self.get(&url)
+    }
+
+    fn crash_counts_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let url = self.url(
+            organization,
+            application,
+            &format!(
+                "errors/errorCountsPerDay?version={}&errorType=unhandledError&{}",
+                percent_encode(version),
+                time_query(time_window)
+            ),
+        );
+        self.get(&url)
+    }
+}
+
+/// The query of the time window. The statistics endpoints require a start, so without a window
+/// the last 30 days are queried like the error groups are by default.
+fn time_query(time_window: Option<TimeWindow>) -> String {
+    match time_window {
+        Some(time_window) => time_window.query(),
+        None => {
+            let start = (Utc::now() - TimeDelta::days(30)).date_naive();
+            format!("start={}", start.format("%Y-%m-%d"))
+        }
+    }
+}
+
+impl AppCenter {
+    /// Sends all requests to the given base url instead of the public AppCenter API, e.g. to a
+    /// caching proxy or a local stub server.
+    ///
+    /// ```
+    /// use recrep::api::{AppCenter, API};
+    ///
+    /// let api = AppCenter::new("abc".to_string()).with_base_url("http://localhost:8080/");
+    /// assert_eq!(api.base_url(), "http://localhost:8080");
+    /// ```
+    pub fn with_base_url(mut self, base_url: &str) -> Self {
+        self.base_url = base_url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Requests all endpoints with the given API version instead of the default one.
+    pub fn with_api_version(mut self, api_version: &str) -> Self {
+        self.api_version = api_version.trim_matches('/').to_string();
+        self
+    }
+
+    /// Sends failed requests again according to the policy. Each request is retried on its own,
+    /// so a failing page of error groups doesn't refetch the pages before it.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Identifies the account and server the responses come from, without revealing the token, so
+    /// responses cached for one of them are never served for another.
+    ///
+    /// ```
+    /// use recrep::api::{AppCenter, API};
+    ///
+    /// let api = AppCenter::new("abc".to_string());
+    /// assert_eq!(api.cache_namespace(), AppCenter::new("abc".to_string()).cache_namespace());
+    /// assert_ne!(api.cache_namespace(), AppCenter::new("xyz".to_string()).cache_namespace());
+    /// assert_ne!(api.cache_namespace(), api.with_base_url("http://localhost").cache_namespace());
+    /// ```
+    pub fn cache_namespace(&self) -> String {
+        let mut hasher = DefaultHasher::new();
+        (&self.base_url, &self.api_version, &self.token).hash(&mut hasher);
+        format!("{:016x}", hasher.finish())
+    }
+
+    /// Builds the url of an endpoint of the given app. The path may contain a query.
+    fn url(&self, organization: &str, application: &str, path: &str) -> String {
+        format!(
+            "{}/{}/apps/{}/{}/{}",
+            self.base_url, self.api_version, organization, application, path
+        )
+    }
+
+    /// Fetches the json at the given url, retrying according to the retry policy, if any.
+    fn get(&self, url: &str) -> Result<String> {
+        match &self.retry_policy {
+            Some(retry_policy) => retry_policy.run(|| self.send(url)),
+            None => self.send(url),
+        }
+    }
+
+    /// Sends a single request, mapping unsuccessful HTTP statuses to errors.
+    fn send(&self, url: &str) -> Result<String> {
+        let mut response = self
             .client
-            .get(&url)
+            .get(url)
             .header("X-API-Token", self.token.clone())
             .header("accept", "application/json")
-            .send();
-        match response {
-            Ok(mut response) => {
-                let mut json = String::new();
-                match response.read_to_string(&mut json) {
-                    Ok(_j) => Ok(json),
-                    Err(e) => panic!("Failed to read response from API. Error: {}", e),
-                }
-            }
-            Err(e) => panic!("Failed to fetch error groups json. Error: {}", e),
+            .send()
+            .map_err(|e| Error::Transport(e.to_string()))?;
+
+        let mut json = String::new();
+        response
+            .read_to_string(&mut json)
+            .map_err(|e| Error::Transport(e.to_string()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(json)
+        } else if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .map(Duration::from_secs);
+            Err(Error::Throttled { retry_after })
+        } else {
+            Err(Error::from_status(status.as_u16(), json))
         }
     }
 }
+
+/// Percent-encodes everything but unreserved characters, so the value can be used in a query.
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{:02X}", byte),
+        })
+        .collect()
+}
+
+/// Extracts the continuation token from a `nextLink` as returned by AppCenter, e.g.
+/// `/api/v0.1/apps/org/app/errors/errorGroups?version=1.0&$top=30&$token=abc`.
+fn continuation_token(next_link: &str) -> Option<String> {
+    let (_, query) = next_link.split_once('?')?;
+    query
+        .split('&')
+        .find_map(|pair| match pair.split_once('=') {
+            Some(("$token", token)) | Some(("%24token", token)) => Some(token.to_string()),
+            _ => None,
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn urls_use_the_configured_base_url_and_api_version() {
+        let api = AppCenter::new("abc".to_string())
+            .with_base_url("http://localhost:8080/proxy/")
+            .with_api_version("v0.2");
+        assert_eq!(
+            api.url("org", "app", "recent_releases"),
+            "http://localhost:8080/proxy/v0.2/apps/org/app/recent_releases"
+        );
+    }
+
+    #[test]
+    fn urls_default_to_the_public_appcenter_api() {
+        let api = AppCenter::new("abc".to_string());
+        assert_eq!(
+            api.url("org", "app", "errors/errorGroups/123u/operatingSystems"),
+            "https://api.appcenter.ms/v0.1/apps/org/app/errors/errorGroups/123u/operatingSystems"
+        );
+    }
+
+    #[test]
+    fn query_values_are_percent_encoded() {
+        assert_eq!(percent_encode("10167"), "10167");
+        assert_eq!(percent_encode("7.41.3-beta_1~2"), "7.41.3-beta_1~2");
+        assert_eq!(percent_encode("1 (a&b=ü)"), "1%20%28a%26b%3D%C3%BC%29");
+        assert_eq!(percent_encode("1.0.0+42"), "1.0.0%2B42");
+        assert_eq!(percent_encode("2.0 beta#1"), "2.0%20beta%231");
+    }
+
+    #[test]
+    fn continuation_token_is_extracted_from_next_link() {
+        let next_link = "/api/v0.1/apps/org/app/errors/errorGroups?version=7.41.3&$orderby=count%20desc&$top=30&$token=123456789";
+        assert_eq!(continuation_token(next_link), Some("123456789".to_string()));
+    }
+
+    #[test]
+    fn continuation_token_supports_encoded_parameter_names() {
+        let next_link = "/api/v0.1/apps/org/app/errors/errorGroups?%24token=abc&%24top=30";
+        assert_eq!(continuation_token(next_link), Some("abc".to_string()));
+    }
+
+    #[test]
+    fn missing_continuation_token() {
+        let next_link = "/api/v0.1/apps/org/app/errors/errorGroups?version=7.41.3";
+        assert_eq!(continuation_token(next_link), None);
+    }
+}
@@ -0,0 +1,414 @@
+use super::api_trait::API;
+use super::{AppCenter, Request};
+use crate::error::Result;
+use crate::model::TimeWindow;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Wraps an API and stores its raw json responses on disk. Responses younger than the time to live
+/// are served from disk instead of being requested again. The latest version is always requested,
+/// so a new release is reported on as soon as it is available.
+///
+/// Each response is stored in its own file at the path of its `Request`, e.g.
+/// `<directory>/org/app/crashes_json-1.2.3-all.json`. Pass a directory specific to the account,
+/// e.g. joined with `AppCenter::cache_namespace`, so responses aren't shared between accounts.
+pub struct CachingAPI {
+    api: Box<dyn API>,
+    directory: PathBuf,
+    time_to_live: Duration,
+}
+
+impl CachingAPI {
+    pub fn wrapping(api: Box<dyn API>, directory: PathBuf, time_to_live: Duration) -> CachingAPI {
+        CachingAPI {
+            api,
+            directory,
+            time_to_live,
+        }
+    }
+
+    /// The cache directory used unless configured otherwise: `$XDG_CACHE_HOME/recrep`,
+    /// `$HOME/.cache/recrep` or a `recrep` folder in the temporary directory.
+    pub fn default_directory() -> PathBuf {
+        env::var_os("XDG_CACHE_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".cache")))
+            .unwrap_or_else(env::temp_dir)
+            .join("recrep")
+    }
+
+    /// Reads the cached response at the path, unless it is missing or expired.
+    fn read(&self, path: &Path) -> Option<String> {
+        let age = fs::metadata(path).ok()?.modified().ok()?.elapsed().ok()?;
+        if age > self.time_to_live {
+            return None;
+        }
+        fs::read_to_string(path).ok()
+    }
+
+    /// Stores the response at the path. A failure only costs a request next time, so it does not
+    /// fail the report.
+    fn write(&self, path: &Path, json: &str) {
+        let result = path
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| fs::write(path, json));
+        if let Err(e) = result {
+            eprintln!("Failed to cache response at {}: {}", path.display(), e);
+        }
+    }
+
+    fn cached(&self, request: Request, fetch: impl FnOnce() -> Result<String>) -> Result<String> {
+        let path = self.directory.join(request.path());
+        if let Some(json) = self.read(&path) {
+            return Ok(json);
+        }
+        let json = fetch()?;
+        self.write(&path, &json);
+        Ok(json)
+    }
+}
+
+impl API for CachingAPI {
+    /// Creates an AppCenter API cached in the default directory for an hour
+    fn new(token: String) -> Self {
+        let api = AppCenter::new(token);
+        let directory = CachingAPI::default_directory().join(api.cache_namespace());
+        CachingAPI::wrapping(Box::new(api), directory, Duration::from_secs(60 * 60))
+    }
+
+    fn latest_version(&self, organization: String, application: String) -> Result<String> {
+        self.api.latest_version(organization, application)
+    }
+
+    fn crashes_json(
+        &self,
+        organization: String,
+        application: String,
+        version: String,
+        build: Option<String>,
+        time_window: Option<TimeWindow>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>> {
+        let request = Request::crashes_json(
+            &organization,
+            &application,
+            &version,
+            build.as_deref(),
+            time_window,
+            limit,
+        );
+        let json = self.cached(request, || {
+            let pages = self.api.crashes_json(
+                organization.clone(),
+                application.clone(),
+                version.clone(),
+                build.clone(),
+                time_window,
+                limit,
+            )?;
+            Ok(json!(pages).to_string())
+        })?;
+
+        crate::json_parsing::from_json(&json)
+    }
+
+    fn os_versions(
+        &self,
+        organization: &str,
+        application: &str,
+        error_group_id: &str,
+    ) -> Result<String> {
+        let request = Request::new("os_versions", organization, application, &[error_group_id]);
+        self.cached(request, || {
+            self.api
+                .os_versions(organization, application, error_group_id)
+        })
+    }
+
+    fn stacktrace_json(
+        &self,
+        organization: &str,
+        application: &str,
+        error_group_id: &str,
+    ) -> Result<String> {
+        let request = Request::new(
+            "stacktrace_json",
+            organization,
+            application,
+            &[error_group_id],
+        );
+        self.cached(request, || {
+            self.api
+                .stacktrace_json(organization, application, error_group_id)
+        })
+    }
+
+    fn crash_free_devices_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let request = Request::statistics(
+            "crash_free_devices_json",
+            organization,
+            application,
+            version,
+            time_window,
+        );
+        self.cached(request, || {
+            self.api
+                .crash_free_devices_json(organization, application, version, time_window)
+        })
+    }
+
+    fn session_counts_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let request = Request::statistics(
+            "session_counts_json",
+            organization,
+            application,
+            version,
+            time_window,
+        );
+        self.cached(request, || {
+            self.api
+                .session_counts_json(organization, application, version, time_window)
+        })
+    }
+
+    fn crash_counts_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let request = Request::statistics(
+            "crash_counts_json",
+            organization,
+            application,
+            version,
+            time_window,
+        );
+        self.cached(request, || {
+            self.api
+                .crash_counts_json(organization, application, version, time_window)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockAPI;
+    use crate::utils::TestHelper;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Counts the requests sent to the wrapped mock.
+    struct CountingAPI {
+        api: MockAPI,
+        requests: Arc<AtomicUsize>,
+    }
+
+    impl API for CountingAPI {
+        fn new(token: String) -> Self {
+            CountingAPI {
+                api: MockAPI::new(token),
+                requests: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+
+        fn latest_version(&self, organization: String, application: String) -> Result<String> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.api.latest_version(organization, application)
+        }
+
+        fn crashes_json(
+            &self,
+            organization: String,
+            application: String,
+            version: String,
+            build: Option<String>,
+            time_window: Option<TimeWindow>,
+            limit: Option<usize>,
+        ) -> Result<Vec<String>> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.api.crashes_json(
+                organization,
+                application,
+                version,
+                build,
+                time_window,
+                limit,
+            )
+        }
+
+        fn os_versions(
+            &self,
+            organization: &str,
+            application: &str,
+            error_group_id: &str,
+        ) -> Result<String> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.api
+                .os_versions(organization, application, error_group_id)
+        }
+
+        fn stacktrace_json(
+            &self,
+            organization: &str,
+            application: &str,
+            error_group_id: &str,
+        ) -> Result<String> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.api
+                .stacktrace_json(organization, application, error_group_id)
+        }
+
+        fn crash_free_devices_json(
+            &self,
+            organization: &str,
+            application: &str,
+            version: &str,
+            time_window: Option<TimeWindow>,
+        ) -> Result<String> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.api
+                .crash_free_devices_json(organization, application, version, time_window)
+        }
+
+        fn session_counts_json(
+            &self,
+            organization: &str,
+            application: &str,
+            version: &str,
+            time_window: Option<TimeWindow>,
+        ) -> Result<String> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.api
+                .session_counts_json(organization, application, version, time_window)
+        }
+
+        fn crash_counts_json(
+            &self,
+            organization: &str,
+            application: &str,
+            version: &str,
+            time_window: Option<TimeWindow>,
+        ) -> Result<String> {
+            self.requests.fetch_add(1, Ordering::SeqCst);
+            self.api
+                .crash_counts_json(organization, application, version, time_window)
+        }
+    }
+
+    fn cache_directory(name: &str) -> PathBuf {
+        let directory = env::temp_dir().join(format!("recrep-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    fn counting_api(requests: &Arc<AtomicUsize>) -> Box<dyn API> {
+        Box::new(CountingAPI {
+            api: MockAPI::with_paginated_crashes()
+                .and_versions_json(TestHelper::fixture(
+                    "src/json_parsing/test_fixtures/latest_version.json",
+                ))
+                .and_operating_systems_json(TestHelper::fixture(
+                    "src/json_parsing/test_fixtures/operating_systems.json",
+                )),
+            requests: requests.clone(),
+        })
+    }
+
+    #[test]
+    fn responses_are_served_from_disk() {
+        let directory = cache_directory("served");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let api = CachingAPI::wrapping(
+            counting_api(&requests),
+            directory.clone(),
+            Duration::from_secs(60),
+        );
+
+        for _ in 0..2 {
+            let pages = api
+                .crashes_json(
+                    "org".to_string(),
+                    "app".to_string(),
+                    "1.0".to_string(),
+                    None,
+                    None,
+                    None,
+                )
+                .unwrap();
+            assert_eq!(pages.len(), 2);
+            api.os_versions("org", "app", "123u").unwrap();
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+        assert!(directory.join("org/app/crashes_json-1.0-all.json").exists());
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn parameters_are_part_of_the_key() {
+        let directory = cache_directory("parameters");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let api = CachingAPI::wrapping(
+            counting_api(&requests),
+            directory.clone(),
+            Duration::from_secs(60),
+        );
+
+        api.os_versions("org", "app", "1u").unwrap();
+        api.os_versions("org", "app", "2u").unwrap();
+        api.os_versions("org", "other app", "1u").unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 3);
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn the_latest_version_is_always_requested() {
+        let directory = cache_directory("latest");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let api = CachingAPI::wrapping(
+            counting_api(&requests),
+            directory.clone(),
+            Duration::from_secs(60),
+        );
+
+        api.latest_version("org".to_string(), "app".to_string())
+            .unwrap();
+        api.latest_version("org".to_string(), "app".to_string())
+            .unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn expired_responses_are_requested_again() {
+        let directory = cache_directory("expired");
+        let requests = Arc::new(AtomicUsize::new(0));
+        let api = CachingAPI::wrapping(counting_api(&requests), directory.clone(), Duration::ZERO);
+
+        api.os_versions("org", "app", "1u").unwrap();
+        api.os_versions("org", "app", "1u").unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+}
@@ -6,3 +6,15 @@ pub mod appcenter_api;
 
 pub use self::api_mock::MockAPI;
 pub mod api_mock;
+
+pub use self::retry_policy::RetryPolicy;
+pub mod retry_policy;
+
+pub use self::caching_api::CachingAPI;
+pub mod caching_api;
+
+pub use self::request::Request;
+pub mod request;
+
+pub use self::recording_api::{Recording, RecordingAPI, ReplayAPI};
+pub mod recording_api;
@@ -0,0 +1,403 @@
+use super::api_trait::API;
+use super::{AppCenter, Request};
+use crate::error::{Error, Result};
+use crate::model::TimeWindow;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// A response stored alongside the request it answers.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Recording {
+    pub request: Request,
+
+    /// The json response. Paginated responses are stored as an array of pages.
+    pub response: Value,
+}
+
+impl Recording {
+    /// Reads the recording of the request from the directory.
+    pub fn read(directory: &Path, request: &Request) -> Result<Recording> {
+        let path = directory.join(request.path());
+        let json = fs::read_to_string(&path).map_err(|_| Error::MissingRecording(path))?;
+        crate::json_parsing::from_json(&json)
+    }
+
+    /// Writes the recording into the directory, replacing a previous recording of the request.
+    pub fn write(&self, directory: &Path) -> Result<()> {
+        let path = directory.join(self.request.path());
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let json = serde_json::to_string_pretty(self).expect("Recordings are serializable");
+        fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+/// Parses a raw response, so the recording stays readable. Responses which aren't valid json are
+/// stored as a string.
+fn response_value(json: &str) -> Value {
+    serde_json::from_str(json).unwrap_or_else(|_| Value::String(json.to_string()))
+}
+
+/// The raw response of a value created by `response_value`.
+fn response_json(value: &Value) -> String {
+    match value {
+        Value::String(json) => json.clone(),
+        _ => value.to_string(),
+    }
+}
+
+/// Wraps an API and writes every response alongside its request into a directory. A `ReplayAPI`
+/// reading this directory answers the same requests without talking to AppCenter.
+pub struct RecordingAPI {
+    api: Box<dyn API>,
+    directory: PathBuf,
+}
+
+impl RecordingAPI {
+    pub fn wrapping(api: Box<dyn API>, directory: PathBuf) -> RecordingAPI {
+        RecordingAPI { api, directory }
+    }
+
+    fn record(&self, request: Request, response: Value) -> Result<()> {
+        Recording { request, response }.write(&self.directory)
+    }
+}
+
+impl API for RecordingAPI {
+    /// Creates an AppCenter API recording into the current directory
+    fn new(token: String) -> Self {
+        RecordingAPI::wrapping(Box::new(AppCenter::new(token)), PathBuf::from("."))
+    }
+
+    fn latest_version(&self, organization: String, application: String) -> Result<String> {
+        let json = self
+            .api
+            .latest_version(organization.clone(), application.clone())?;
+        let request = Request::new("latest_version", &organization, &application, &[]);
+        self.record(request, response_value(&json))?;
+        Ok(json)
+    }
+
+    fn crashes_json(
+        &self,
+        organization: String,
+        application: String,
+        version: String,
+        build: Option<String>,
+        time_window: Option<TimeWindow>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>> {
+        let pages = self.api.crashes_json(
+            organization.clone(),
+            application.clone(),
+            version.clone(),
+            build.clone(),
+            time_window,
+            limit,
+        )?;
+        let request = Request::crashes_json(
+            &organization,
+            &application,
+            &version,
+            build.as_deref(),
+            time_window,
+            limit,
+        );
+        let response = Value::Array(pages.iter().map(|page| response_value(page)).collect());
+        self.record(request, response)?;
+        Ok(pages)
+    }
+
+    fn os_versions(
+        &self,
+        organization: &str,
+        application: &str,
+        error_group_id: &str,
+    ) -> Result<String> {
+        let json = self
+            .api
+            .os_versions(organization, application, error_group_id)?;
+        let request = Request::new("os_versions", organization, application, &[error_group_id]);
+        self.record(request, response_value(&json))?;
+        Ok(json)
+    }
+
+    fn stacktrace_json(
+        &self,
+        organization: &str,
+        application: &str,
+        error_group_id: &str,
+    ) -> Result<String> {
+        let json = self
+            .api
+            .stacktrace_json(organization, application, error_group_id)?;
+        let request = Request::new(
+            "stacktrace_json",
+            organization,
+            application,
+            &[error_group_id],
+        );
+        self.record(request, response_value(&json))?;
+        Ok(json)
+    }
+
+    fn crash_free_devices_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let json =
+            self.api
+                .crash_free_devices_json(organization, application, version, time_window)?;
+        let request = Request::statistics(
+            "crash_free_devices_json",
+            organization,
+            application,
+            version,
+            time_window,
+        );
+        self.record(request, response_value(&json))?;
+        Ok(json)
+    }
+
+    fn session_counts_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let json = self
+            .api
+            .session_counts_json(organization, application, version, time_window)?;
+        let request = Request::statistics(
+            "session_counts_json",
+            organization,
+            application,
+            version,
+            time_window,
+        );
+        self.record(request, response_value(&json))?;
+        Ok(json)
+    }
+
+    fn crash_counts_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let json = self
+            .api
+            .crash_counts_json(organization, application, version, time_window)?;
+        let request = Request::statistics(
+            "crash_counts_json",
+            organization,
+            application,
+            version,
+            time_window,
+        );
+        self.record(request, response_value(&json))?;
+        Ok(json)
+    }
+}
+
+/// Answers requests with the responses recorded by a `RecordingAPI`. Requests which weren't
+/// recorded fail with `Error::MissingRecording`.
+///
+/// ```
+/// use recrep::api::{ReplayAPI, API};
+///
+/// let api = ReplayAPI::from_directory("src/json_parsing/test_fixtures/recording".into());
+/// let pages = api
+///     .crashes_json("org".to_string(), "app".to_string(), "7.41.3".to_string(), None, None, None)
+///     .unwrap();
+/// assert_eq!(pages.len(), 2);
+/// ```
+pub struct ReplayAPI {
+    directory: PathBuf,
+}
+
+impl ReplayAPI {
+    pub fn from_directory(directory: PathBuf) -> ReplayAPI {
+        ReplayAPI { directory }
+    }
+
+    fn replay(&self, request: Request) -> Result<Value> {
+        Ok(Recording::read(&self.directory, &request)?.response)
+    }
+}
+
+impl API for ReplayAPI {
+    /// Creates an API replaying the recordings in the current directory
+    fn new(_token: String) -> Self {
+        ReplayAPI::from_directory(PathBuf::from("."))
+    }
+
+    fn latest_version(&self, organization: String, application: String) -> Result<String> {
+        let request = Request::new("latest_version", &organization, &application, &[]);
+        Ok(response_json(&self.replay(request)?))
+    }
+
+    fn crashes_json(
+        &self,
+        organization: String,
+        application: String,
+        version: String,
+        build: Option<String>,
+        time_window: Option<TimeWindow>,
+        limit: Option<usize>,
+    ) -> Result<Vec<String>> {
+        let request = Request::crashes_json(
+            &organization,
+            &application,
+            &version,
+            build.as_deref(),
+            time_window,
+            limit,
+        );
+        match self.replay(request)? {
+            Value::Array(pages) => Ok(pages.iter().map(response_json).collect()),
+            page => Ok(vec![response_json(&page)]),
+        }
+    }
+
+    fn os_versions(
+        &self,
+        organization: &str,
+        application: &str,
+        error_group_id: &str,
+    ) -> Result<String> {
+        let request = Request::new("os_versions", organization, application, &[error_group_id]);
+        Ok(response_json(&self.replay(request)?))
+    }
+
+    fn stacktrace_json(
+        &self,
+        organization: &str,
+        application: &str,
+        error_group_id: &str,
+    ) -> Result<String> {
+        let request = Request::new(
+            "stacktrace_json",
+            organization,
+            application,
+            &[error_group_id],
+        );
+        Ok(response_json(&self.replay(request)?))
+    }
+
+    fn crash_free_devices_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let request = Request::statistics(
+            "crash_free_devices_json",
+            organization,
+            application,
+            version,
+            time_window,
+        );
+        Ok(response_json(&self.replay(request)?))
+    }
+
+    fn session_counts_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let request = Request::statistics(
+            "session_counts_json",
+            organization,
+            application,
+            version,
+            time_window,
+        );
+        Ok(response_json(&self.replay(request)?))
+    }
+
+    fn crash_counts_json(
+        &self,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Result<String> {
+        let request = Request::statistics(
+            "crash_counts_json",
+            organization,
+            application,
+            version,
+            time_window,
+        );
+        Ok(response_json(&self.replay(request)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockAPI;
+    use crate::crashes::CrashManager;
+    use crate::utils::TestHelper;
+    use std::env;
+
+    #[test]
+    fn recorded_responses_are_replayed() {
+        let directory = env::temp_dir().join(format!("recrep-recording-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        let mock = MockAPI::with_paginated_crashes()
+            .and_versions_json(TestHelper::fixture(
+                "src/json_parsing/test_fixtures/latest_version.json",
+            ))
+            .and_operating_systems_json(TestHelper::fixture(
+                "src/json_parsing/test_fixtures/operating_systems.json",
+            ));
+        let recording = RecordingAPI::wrapping(Box::new(mock), directory.clone());
+        let replay = ReplayAPI::from_directory(directory.clone());
+        let manager = CrashManager::default();
+
+        let recorded = manager
+            .crash_list(&recording, "org", "app", None, None, Some(4))
+            .unwrap();
+        let recorded_details = manager
+            .error_group_details(&recording, "92620314", "app", "org")
+            .unwrap();
+        let replayed = manager
+            .crash_list(&replay, "org", "app", None, None, Some(4))
+            .unwrap();
+        let replayed_details = manager
+            .error_group_details(&replay, "92620314", "app", "org")
+            .unwrap();
+
+        assert_eq!(replayed.version, recorded.version);
+        assert_eq!(
+            json!(replayed.crash_list).to_string(),
+            json!(recorded.crash_list).to_string()
+        );
+        assert_eq!(replayed_details.count, recorded_details.count);
+
+        let _ = fs::remove_dir_all(&directory);
+    }
+
+    #[test]
+    fn requests_without_recording_fail() {
+        let replay = ReplayAPI::from_directory(PathBuf::from("src/json_parsing/test_fixtures"));
+        assert!(matches!(
+            replay.os_versions("org", "app", "123u"),
+            Err(Error::MissingRecording(_))
+        ));
+    }
+}
@@ -0,0 +1,140 @@
+use crate::model::TimeWindow;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// Describes a call of an API trait method. It identifies responses stored on disk.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Request {
+    /// The name of the API trait method, e.g. `crashes_json`.
+    pub endpoint: String,
+
+    pub organization: String,
+
+    pub application: String,
+
+    /// The remaining parameters of the method in order of declaration.
+    pub parameters: Vec<String>,
+}
+
+impl Request {
+    pub fn new(
+        endpoint: &str,
+        organization: &str,
+        application: &str,
+        parameters: &[&str],
+    ) -> Request {
+        Request {
+            endpoint: endpoint.to_string(),
+            organization: organization.to_string(),
+            application: application.to_string(),
+            parameters: parameters.iter().map(|p| p.to_string()).collect(),
+        }
+    }
+
+    /// The request of the error groups of a version, or of a single build of it. The build and
+    /// time window are omitted unless given, so recordings of whole versions stay valid.
+    ///
+    /// ```
+    /// use recrep::api::Request;
+    /// use std::path::Path;
+    ///
+    /// let request = Request::crashes_json("org", "app", "1.2.3", Some("10167"), None, Some(5));
+    /// assert_eq!(request.path(), Path::new("org/app/crashes_json-1.2.3-10167-5.json"));
+    /// ```
+    pub fn crashes_json(
+        organization: &str,
+        application: &str,
+        version: &str,
+        build: Option<&str>,
+        time_window: Option<TimeWindow>,
+        limit: Option<usize>,
+    ) -> Request {
+        let mut parameters = vec![version.to_string()];
+        parameters.extend(build.map(|build| build.to_string()));
+        parameters.extend(time_window.map(|window| window.request_parameter()));
+        parameters.push(Request::limit_parameter(limit));
+
+        let parameters: Vec<&str> = parameters.iter().map(|p| p.as_str()).collect();
+        Request::new("crashes_json", organization, application, &parameters)
+    }
+
+    /// The request of a statistic of a version, e.g. `session_counts_json`.
+    pub fn statistics(
+        endpoint: &str,
+        organization: &str,
+        application: &str,
+        version: &str,
+        time_window: Option<TimeWindow>,
+    ) -> Request {
+        let mut parameters = vec![version.to_string()];
+        parameters.extend(time_window.map(|window| window.request_parameter()));
+
+        let parameters: Vec<&str> = parameters.iter().map(|p| p.as_str()).collect();
+        Request::new(endpoint, organization, application, &parameters)
+    }
+
+    /// The parameter representing an optional limit of error groups.
+    pub fn limit_parameter(limit: Option<usize>) -> String {
+        limit.map_or("all".to_string(), |limit| limit.to_string())
+    }
+
+    /// The path of the file storing the response, relative to a storage directory.
+    ///
+    /// ```
+    /// use recrep::api::Request;
+    /// use std::path::Path;
+    ///
+    /// let request = Request::new("crashes_json", "org", "my app", &["1.2.3", "all"]);
+    /// assert_eq!(request.path(), Path::new("org/my_app/crashes_json-1.2.3-all.json"));
+    /// ```
+    pub fn path(&self) -> PathBuf {
+        let mut file_name = self.endpoint.clone();
+        for parameter in &self.parameters {
+            file_name.push('-');
+            file_name.push_str(parameter);
+        }
+        file_name.push_str(".json");
+
+        PathBuf::from(sanitize(&self.organization))
+            .join(sanitize(&self.application))
+            .join(sanitize(&file_name))
+    }
+}
+
+/// Replaces everything but alphanumerics, dots, dashes and underscores, as well as leading dots, so
+/// keys are valid file names which stay inside the storage directory.
+fn sanitize(key: &str) -> String {
+    let sanitized: String = key
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+            _ => '_',
+        })
+        .collect();
+    let without_leading_dots = sanitized.trim_start_matches('.');
+    "_".repeat(sanitized.len() - without_leading_dots.len()) + without_leading_dots
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys_are_sanitized() {
+        assert_eq!(sanitize("../1.2.3 (4)"), "___1.2.3__4_");
+        assert_eq!(sanitize(".."), "__");
+    }
+
+    #[test]
+    fn time_windows_are_part_of_the_key() {
+        let window = TimeWindow::new(
+            "2019-05-15T00:00:00Z".parse().unwrap(),
+            "2019-05-16T00:00:00Z".parse().unwrap(),
+        );
+        let request = Request::crashes_json("org", "app", "1.2.3", None, Some(window), None);
+        assert_eq!(
+            request.path(),
+            PathBuf::from("org/app/crashes_json-1.2.3-20190515T000000Z-20190516T000000Z-all.json")
+        );
+    }
+}
@@ -0,0 +1,199 @@
+use crate::error::{Error, Result};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::thread;
+use std::time::Duration;
+
+/// Describes how often and how long to wait before a failed request is sent again.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    /// The amount of retries after the first attempt failed.
+    pub max_retries: u32,
+
+    /// The delay before the first retry. It doubles with every further retry.
+    pub initial_backoff: Duration,
+
+    /// The upper bound of the delay between two attempts.
+    pub max_backoff: Duration,
+
+    /// Randomizes each delay between half and the full backoff, so concurrent requests don't
+    /// retry in lockstep.
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_retries: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Runs the operation until it succeeds, fails with a permanent error or the retries are
+    /// exhausted. A `Retry-After` delay requested by AppCenter takes precedence over the backoff,
+    /// but is capped by the maximum backoff as well.
+    ///
+    /// ```
+    /// use recrep::api::RetryPolicy;
+    /// use recrep::Error;
+    /// use std::time::Duration;
+    ///
+    /// let policy = RetryPolicy {
+    ///     max_retries: 2,
+    ///     initial_backoff: Duration::from_millis(1),
+    ///     ..RetryPolicy::default()
+    /// };
+    ///
+    /// let mut attempts = 0;
+    /// let result = policy.run(|| {
+    ///     attempts += 1;
+    ///     if attempts < 3 {
+    ///         Err(Error::from_status(503, String::new()))
+    ///     } else {
+    ///         Ok(attempts)
+    ///     }
+    /// });
+    /// assert_eq!(result.unwrap(), 3);
+    /// ```
+    pub fn run<T>(&self, mut operation: impl FnMut() -> Result<T>) -> Result<T> {
+        let mut retry = 0;
+        loop {
+            match operation() {
+                Err(e) if e.is_transient() && retry < self.max_retries => {
+                    let delay = match e {
+                        Error::Throttled {
+                            retry_after: Some(retry_after),
+                        } => retry_after.min(self.max_backoff),
+                        _ => self.backoff(retry),
+                    };
+                    thread::sleep(delay);
+                    retry += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// The delay before the given retry, starting with 0 for the first retry.
+    fn backoff(&self, retry: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .checked_mul(2u32.saturating_pow(retry))
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff);
+
+        if self.jitter {
+            backoff / 2 + backoff.mul_f64(random_fraction() / 2.0)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// A random number in `[0, 1)`, seeded by the randomly keyed std hasher.
+fn random_fraction() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u32(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    fn fast_policy(max_retries: u32) -> RetryPolicy {
+        RetryPolicy {
+            max_retries,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            jitter: true,
+        }
+    }
+
+    /// Fails with the given error `failures` times, then succeeds. Returns the result and the
+    /// amount of attempts.
+    fn run_flaky(policy: &RetryPolicy, failures: u32, error: fn() -> Error) -> (Result<()>, u32) {
+        let mut attempts = 0;
+        let result = policy.run(|| {
+            attempts += 1;
+            if attempts <= failures {
+                Err(error())
+            } else {
+                Ok(())
+            }
+        });
+        (result, attempts)
+    }
+
+    #[test]
+    fn throttled_requests_are_retried_after_the_requested_delay() {
+        let policy = RetryPolicy {
+            max_backoff: Duration::from_secs(1),
+            ..fast_policy(2)
+        };
+        let started = Instant::now();
+        let (result, attempts) = run_flaky(&policy, 2, || Error::Throttled {
+            retry_after: Some(Duration::from_millis(30)),
+        });
+
+        assert!(result.is_ok());
+        assert_eq!(attempts, 3);
+        assert!(started.elapsed() >= Duration::from_millis(60));
+    }
+
+    #[test]
+    fn requested_delays_are_capped_by_the_maximum_backoff() {
+        let started = Instant::now();
+        let (result, _) = run_flaky(&fast_policy(1), 1, || Error::Throttled {
+            retry_after: Some(Duration::from_secs(60)),
+        });
+
+        assert!(result.is_ok());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+
+    #[test]
+    fn retries_are_limited() {
+        let (result, attempts) = run_flaky(&fast_policy(2), 3, || {
+            Error::Transport("connection reset".to_string())
+        });
+        assert!(matches!(result, Err(Error::Transport(_))));
+        assert_eq!(attempts, 3);
+    }
+
+    #[test]
+    fn permanent_errors_are_not_retried() {
+        let (result, attempts) = run_flaky(&fast_policy(2), 1, || Error::Unauthorized);
+        assert!(matches!(result, Err(Error::Unauthorized)));
+        assert_eq!(attempts, 1);
+    }
+
+    #[test]
+    fn backoff_doubles_up_to_the_maximum() {
+        let policy = RetryPolicy {
+            max_retries: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(5),
+            jitter: false,
+        };
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(1), Duration::from_secs(2));
+        assert_eq!(policy.backoff(2), Duration::from_secs(4));
+        assert_eq!(policy.backoff(3), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn jitter_stays_between_half_and_full_backoff() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::from_secs(2),
+            ..RetryPolicy::default()
+        };
+        let backoff = policy.backoff(0);
+        assert!(backoff >= Duration::from_secs(1) && backoff <= Duration::from_secs(2));
+    }
+}
@@ -0,0 +1,416 @@
+use crate::api::{AppCenter, API};
+use crate::error::{Error, Result};
+use crate::model::{ErrorGroupState, TimeWindow, VersionOrdering};
+use crate::utils::{FileWriter, StdOutPrinter};
+use crate::{CrashReporter, CsvColumn, Format};
+use chrono::{DateTime, Utc};
+use handlebars::{html_escape, no_escape, Handlebars};
+
+/// The amount of concurrent requests for error group details or stack traces, unless configured otherwise.
+const DEFAULT_PARALLEL_REQUESTS: usize = 4;
+
+/// The percentage a crash count has to change by to count as regression, unless configured
+/// otherwise.
+const DEFAULT_REGRESSION_THRESHOLD: f64 = 10.0;
+
+/// Builds a `CrashReporter`. Only the token, organization and application are required, all
+/// other options default to a plain report of the latest version.
+///
+/// ```
+/// use recrep::CrashReporterBuilder;
+///
+/// let reporter = CrashReporterBuilder::new("abc", "org", "app")
+///     .version("1.2.3")
+///     .threshold(300)
+///     .show_os_information(true)
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!("abc", reporter.token);
+/// ```
+pub struct CrashReporterBuilder {
+    token: String,
+    organization: String,
+    application: String,
+    version: Option<String>,
+    build: Option<String>,
+    since: Option<DateTime<Utc>>,
+    time_window: Option<TimeWindow>,
+    distribution_group: Option<String>,
+    threshold: Option<u64>,
+    min_crash_free_users: Option<f64>,
+    min_crash_free_sessions: Option<f64>,
+    use_arithmetic_mean: bool,
+    show_os_information: bool,
+    stacktrace_frames: Option<usize>,
+    break_down_by_build: bool,
+    filter_out_errors: bool,
+    states: Vec<ErrorGroupState>,
+    error_group_limit: Option<usize>,
+    parallel_requests: usize,
+    base_version: Option<String>,
+    regression_threshold: f64,
+    version_ordering: VersionOrdering,
+    format: Format,
+    columns: Option<Vec<CsvColumn>>,
+    template: Option<String>,
+    empty_template: Option<String>,
+    partials: Vec<(String, String)>,
+    api: Option<Box<dyn API>>,
+}
+
+impl CrashReporterBuilder {
+    pub fn new(token: &str, organization: &str, application: &str) -> CrashReporterBuilder {
+        CrashReporterBuilder {
+            token: token.to_string(),
+            organization: organization.to_string(),
+            application: application.to_string(),
+            version: None,
+            build: None,
+            since: None,
+            time_window: None,
+            distribution_group: None,
+            threshold: None,
+            min_crash_free_users: None,
+            min_crash_free_sessions: None,
+            use_arithmetic_mean: false,
+            show_os_information: false,
+            stacktrace_frames: None,
+            break_down_by_build: false,
+            filter_out_errors: false,
+            states: Vec::new(),
+            error_group_limit: None,
+            parallel_requests: DEFAULT_PARALLEL_REQUESTS,
+            base_version: None,
+            regression_threshold: DEFAULT_REGRESSION_THRESHOLD,
+            version_ordering: VersionOrdering::default(),
+            format: Format::default(),
+            columns: None,
+            template: None,
+            empty_template: None,
+            partials: Vec::new(),
+            api: None,
+        }
+    }
+
+    /// The version to report on. Defaults to the latest version.
+    pub fn version(mut self, version: &str) -> Self {
+        self.version = Some(version.to_string());
+        self
+    }
+
+    /// Restricts the report to a single build of the version, e.g. `10167`. Requires a version.
+    pub fn app_build(mut self, build: &str) -> Self {
+        self.build = Some(build.to_string());
+        self
+    }
+
+    /// Combines the crashes of all versions uploaded since the given point in time into one
+    /// report. Incompatible with a version.
+    pub fn since(mut self, since: DateTime<Utc>) -> Self {
+        self.since = Some(since);
+        self
+    }
+
+    /// Only count the crashes which occurred within the window, e.g. the last 24 hours. Defaults to
+    /// the period AppCenter uses.
+    pub fn time_window(mut self, time_window: TimeWindow) -> Self {
+        self.time_window = Some(time_window);
+        self
+    }
+
+    /// The distribution group used to find the latest version, or the versions of a date range.
+    /// Ignored if a version is set.
+    pub fn distribution_group(mut self, distribution_group: &str) -> Self {
+        self.distribution_group = Some(distribution_group.to_string());
+        self
+    }
+
+    /// The amount of crashes representing 100%. Incompatible with the arithmetic mean.
+    pub fn threshold(mut self, threshold: u64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// The percentage of users without crashes the version must reach, e.g. `99.5`. Lower rates
+    /// are marked in the report and count as exceeded threshold.
+    pub fn min_crash_free_users(mut self, min_crash_free_users: f64) -> Self {
+        self.min_crash_free_users = Some(min_crash_free_users);
+        self
+    }
+
+    /// The percentage of sessions without crashes the version must reach, e.g. `99.9`. Lower rates
+    /// are marked in the report and count as exceeded threshold.
+    pub fn min_crash_free_sessions(mut self, min_crash_free_sessions: f64) -> Self {
+        self.min_crash_free_sessions = Some(min_crash_free_sessions);
+        self
+    }
+
+    /// Only report crashes occurring at least as often as the arithmetic mean of all crashes.
+    /// Incompatible with a threshold.
+    pub fn use_arithmetic_mean(mut self, use_arithmetic_mean: bool) -> Self {
+        self.use_arithmetic_mean = use_arithmetic_mean;
+        self
+    }
+
+    /// Fetch and show the operating systems affected by each crash.
+    pub fn show_os_information(mut self, show_os_information: bool) -> Self {
+        self.show_os_information = show_os_information;
+        self
+    }
+
+    /// Fetch the stack trace of each crash and show its topmost `frames` frames.
+    pub fn stacktrace_frames(mut self, frames: usize) -> Self {
+        self.stacktrace_frames = Some(frames);
+        self
+    }
+
+    /// Break the counts of each crash down per build, if the version has several builds.
+    pub fn break_down_by_build(mut self, break_down_by_build: bool) -> Self {
+        self.break_down_by_build = break_down_by_build;
+        self
+    }
+
+    /// Omit error groups classified as errors from the report.
+    pub fn filter_out_errors(mut self, filter_out_errors: bool) -> Self {
+        self.filter_out_errors = filter_out_errors;
+        self
+    }
+
+    /// Only report error groups in one of the given states, e.g. open and reopened ones. Defaults
+    /// to all states.
+    pub fn states(mut self, states: Vec<ErrorGroupState>) -> Self {
+        self.states = states;
+        self
+    }
+
+    /// The maximum amount of error groups to fetch. Defaults to all error groups.
+    pub fn error_group_limit(mut self, error_group_limit: usize) -> Self {
+        self.error_group_limit = Some(error_group_limit);
+        self
+    }
+
+    /// The maximum amount of concurrent requests for error group details or stack traces. Defaults
+    /// to 4.
+    pub fn parallel_requests(mut self, parallel_requests: usize) -> Self {
+        self.parallel_requests = parallel_requests;
+        self
+    }
+
+    /// Compare the crashes with the ones of the given base version, e.g. the previous release.
+    pub fn compare_to(mut self, base_version: &str) -> Self {
+        self.base_version = Some(base_version.to_string());
+        self
+    }
+
+    /// The percentage a crash count has to change by compared to the base version to count as
+    /// regression or improvement. Defaults to 10%.
+    pub fn regression_threshold(mut self, regression_threshold: f64) -> Self {
+        self.regression_threshold = regression_threshold;
+        self
+    }
+
+    /// How versions are ordered to find the latest ones. Defaults to the upload date.
+    pub fn version_ordering(mut self, version_ordering: VersionOrdering) -> Self {
+        self.version_ordering = version_ordering;
+        self
+    }
+
+    /// The format reports and trends are written in. Defaults to text.
+    pub fn format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// The columns of the CSV and TSV formats, in the given order. Defaults to all columns.
+    pub fn columns(mut self, columns: Vec<CsvColumn>) -> Self {
+        self.columns = Some(columns);
+        self
+    }
+
+    /// A Handlebars template replacing the newsletter of the text, Markdown and HTML formats. See
+    /// the readme for the available variables.
+    pub fn template(mut self, template: &str) -> Self {
+        self.template = Some(template.to_string());
+        self
+    }
+
+    /// A Handlebars template replacing the newsletter of versions without crashes. Defaults to the
+    /// custom template, if any.
+    pub fn empty_template(mut self, empty_template: &str) -> Self {
+        self.empty_template = Some(empty_template.to_string());
+        self
+    }
+
+    /// A Handlebars partial the templates can include with `{{> name}}`.
+    pub fn partial(mut self, name: &str, partial: &str) -> Self {
+        self.partials.push((name.to_string(), partial.to_string()));
+        self
+    }
+
+    /// The API to fetch crashes from. Defaults to AppCenter, authenticated with the token.
+    pub fn api(mut self, api: Box<dyn API>) -> Self {
+        self.api = Some(api);
+        self
+    }
+
+    /// Validates the options, compiles the templates and creates the `CrashReporter`.
+    ///
+    /// ```
+    /// use recrep::{CrashReporterBuilder, Error};
+    ///
+    /// let result = CrashReporterBuilder::new("abc", "org", "app")
+    ///     .threshold(300)
+    ///     .use_arithmetic_mean(true)
+    ///     .build();
+    ///
+    /// assert!(matches!(result, Err(Error::InvalidOptions(_))));
+    /// ```
+    pub fn build(self) -> Result<CrashReporter> {
+        if self.token.is_empty() && self.api.is_none() {
+            return Err(Error::InvalidOptions("The token must not be empty."));
+        }
+        if self.organization.is_empty() || self.application.is_empty() {
+            return Err(Error::InvalidOptions(
+                "The organization and application must not be empty.",
+            ));
+        }
+        if self.threshold.is_some() && self.use_arithmetic_mean {
+            return Err(Error::InvalidOptions(
+                "A threshold can't be combined with the arithmetic mean.",
+            ));
+        }
+        if self.threshold == Some(0) {
+            return Err(Error::InvalidOptions(
+                "The threshold must be greater than 0.",
+            ));
+        }
+        if [self.min_crash_free_users, self.min_crash_free_sessions]
+            .iter()
+            .flatten()
+            .any(|minimum| !(0.0..=100.0).contains(minimum))
+        {
+            return Err(Error::InvalidOptions(
+                "The minimum crash-free rates must be percentages between 0 and 100.",
+            ));
+        }
+        if self.since.is_some()
+            && (self.min_crash_free_users.is_some() || self.min_crash_free_sessions.is_some())
+        {
+            return Err(Error::InvalidOptions(
+                "Crash-free rates are only known for a single version, not for a date range.",
+            ));
+        }
+        if self.build.is_some()
+            && (self.min_crash_free_users.is_some() || self.min_crash_free_sessions.is_some())
+        {
+            return Err(Error::InvalidOptions(
+                "Crash-free rates are only known for a whole version, not for a single build.",
+            ));
+        }
+        if self.build.is_some() && self.version.is_none() {
+            return Err(Error::InvalidOptions(
+                "A build can only be reported on together with its version.",
+            ));
+        }
+        if self.since.is_some() && (self.version.is_some() || self.break_down_by_build) {
+            return Err(Error::InvalidOptions(
+                "A date range can't be combined with a version or a breakdown per build.",
+            ));
+        }
+        if self
+            .time_window
+            .is_some_and(|time_window| time_window.end <= time_window.start)
+        {
+            return Err(Error::InvalidOptions(
+                "The time window must end after it starts.",
+            ));
+        }
+        if self.parallel_requests == 0 {
+            return Err(Error::InvalidOptions(
+                "At least one parallel request is required.",
+            ));
+        }
+        if !self.regression_threshold.is_finite() || self.regression_threshold < 0.0 {
+            return Err(Error::InvalidOptions(
+                "The regression threshold must be a percentage of at least 0.",
+            ));
+        }
+        if let Some(columns) = &self.columns {
+            if !matches!(self.format, Format::Csv | Format::Tsv) {
+                return Err(Error::InvalidOptions(
+                    "Columns can only be selected for the csv and tsv formats.",
+                ));
+            }
+            if columns.is_empty() {
+                return Err(Error::InvalidOptions("At least one column is required."));
+            }
+        }
+        if (self.template.is_some() || self.empty_template.is_some())
+            && matches!(self.format, Format::Json | Format::Csv | Format::Tsv)
+        {
+            return Err(Error::InvalidOptions(
+                "Templates can only be used with the text, markdown and html formats.",
+            ));
+        }
+        if self.stacktrace_frames == Some(0) {
+            return Err(Error::InvalidOptions(
+                "At least one stack trace frame must be shown.",
+            ));
+        }
+        if self.error_group_limit.is_some() && self.base_version.is_some() {
+            return Err(Error::InvalidOptions(
+                "A limit can't be combined with a comparison, as crashes beyond it would count as new or resolved.",
+            ));
+        }
+        if self.error_group_limit == Some(0) {
+            return Err(Error::InvalidOptions(
+                "The error group limit must be greater than 0.",
+            ));
+        }
+
+        let token = self.token;
+        let api = self
+            .api
+            .unwrap_or_else(|| Box::new(AppCenter::new(token.clone())));
+
+        let mut reporter = CrashReporter {
+            token,
+            organization: self.organization,
+            application: self.application,
+            version: self.version,
+            build: self.build,
+            since: self.since,
+            time_window: self.time_window,
+            distribution_group: self.distribution_group,
+            threshold: self.threshold,
+            min_crash_free_users: self.min_crash_free_users,
+            min_crash_free_sessions: self.min_crash_free_sessions,
+            use_arithmetic_mean: self.use_arithmetic_mean,
+            show_os_information: self.show_os_information,
+            stacktrace_frames: self.stacktrace_frames,
+            break_down_by_build: self.break_down_by_build,
+            filter_out_errors: self.filter_out_errors,
+            states: self.states,
+            error_group_limit: self.error_group_limit,
+            parallel_requests: self.parallel_requests,
+            base_version: self.base_version,
+            regression_threshold: self.regression_threshold,
+            version_ordering: self.version_ordering,
+            format: self.format,
+            columns: self.columns.unwrap_or_else(|| CsvColumn::ALL.to_vec()),
+            template: self.template,
+            empty_template: self.empty_template,
+            partials: self.partials,
+            templates: Handlebars::new(),
+            html_templates: Handlebars::new(),
+            api,
+            file_writer: &FileWriter {},
+            printer: &StdOutPrinter {},
+        };
+        // fail before fetching any crashes if a custom template doesn't compile
+        reporter.templates = reporter.compile_templates(no_escape)?;
+        reporter.html_templates = reporter.compile_templates(html_escape)?;
+        Ok(reporter)
+    }
+}
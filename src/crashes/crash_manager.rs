@@ -1,15 +1,33 @@
 use crate::api::API;
-use crate::json_parsing::crash_parsing;
+use crate::error::{Error, Result};
 use crate::json_parsing::version_parsing::VersionListParser;
-use crate::model::{ErrorGroup, Report, VersionList};
+use crate::json_parsing::{crash_parsing, statistics_parsing};
+use crate::model::{
+    CrashFreeStatistics, ErrorGroup, ErrorGroupState, Report, StackTrace, TimeWindow, Version,
+    VersionList, VersionOrdering,
+};
+use chrono::{DateTime, TimeDelta, Utc};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
 /// The `CrashManager` is responsible to get crash data from its API.
 /// It transforms crash data into structs using a the `CrashParser`.
-pub struct CrashManager {}
+#[derive(Default)]
+pub struct CrashManager {
+    /// How versions are ordered to find the latest ones.
+    pub version_ordering: VersionOrdering,
+
+    /// Only count the crashes which occurred within this window. Defaults to the period AppCenter
+    /// uses if none is given.
+    pub time_window: Option<TimeWindow>,
+
+    /// Only report error groups in one of these states. Reports all error groups if empty.
+    pub states: Vec<ErrorGroupState>,
+}
 
 impl CrashManager {
     /// Fetches crash groups from the API. If no version is provided, the latest version of the
-    /// given app will be used
+    /// given app will be used. All error groups are fetched unless a limit is given.
     ///
     /// ```
     /// # use recrep::api::MockAPI;
@@ -17,67 +35,252 @@ impl CrashManager {
     /// # let api = MockAPI::with_two_crashes();
     /// #
     /// // api is a mock that returns 2 crashes
-    /// let manager = CrashManager{};
-    /// let report = manager.crash_list(&api, "org", "app", Some("1.2.3".to_string()), None).unwrap();
+    /// let manager = CrashManager::default();
+    /// let report = manager.crash_list(&api, "org", "app", Some("1.2.3".to_string()), None, None).unwrap();
     ///
     /// assert_eq!(report.crash_list.crashes.len(), 2);
     /// assert_eq!(report.version, "1.2.3");
     /// ```
+    ///
+    /// Pages of error groups are merged into one crash list:
+    ///
+    /// ```
+    /// # use recrep::api::MockAPI;
+    /// # use recrep::crashes::CrashManager;
+    /// // api is a mock that returns 5 crashes on 2 pages
+    /// let api = MockAPI::with_paginated_crashes();
+    /// let manager = CrashManager::default();
+    ///
+    /// let report = manager.crash_list(&api, "org", "app", Some("1.2.3".to_string()), None, None).unwrap();
+    /// assert_eq!(report.crash_list.crashes.len(), 5);
+    ///
+    /// let report = manager.crash_list(&api, "org", "app", Some("1.2.3".to_string()), None, Some(4)).unwrap();
+    /// assert_eq!(report.crash_list.crashes.len(), 4);
+    /// ```
     pub fn crash_list(
         &self,
-        api: &impl API,
+        api: &dyn API,
         organization: &str,
         application: &str,
         version: Option<String>,
         distribution_group: Option<String>,
-    ) -> Result<Report, &'static str> {
+        limit: Option<usize>,
+    ) -> Result<Report> {
         match version {
             Some(version) => self.crash_list_for_version(
                 api,
                 organization.to_string(),
                 application.to_string(),
                 version,
+                None,
+                limit,
             ),
             None => self.crash_list_for_latest_version(
                 api,
                 organization.to_string(),
                 application.to_string(),
                 distribution_group,
+                limit,
             ),
         }
     }
 
+    /// Fetches the crash groups of every version uploaded since the given point in time, optionally
+    /// only of versions released into a distribution group, and combines them into one report. The
+    /// limit applies to the combined error groups, so all error groups of each version are fetched.
+    ///
+    /// ```
+    /// # use recrep::api::MockAPI;
+    /// # use recrep::crashes::CrashManager;
+    /// # use recrep::utils::TestHelper;
+    /// let api = MockAPI::with_two_crashes().and_versions_json(TestHelper::fixture(
+    ///     "src/json_parsing/test_fixtures/versions.json",
+    /// ));
+    /// let manager = CrashManager::default();
+    /// let since = "2019-05-10T00:00:00Z".parse().unwrap();
+    ///
+    /// let report = manager.crash_list_since(&api, "org", "app", since, None, None).unwrap();
+    /// assert_eq!(report.versions, vec!["7.42.0", "7.41.3"]);
+    /// assert_eq!(report.crash_list.crashes[0].count, Some(30));
+    /// ```
+    pub fn crash_list_since(
+        &self,
+        api: &dyn API,
+        organization: &str,
+        application: &str,
+        since: DateTime<Utc>,
+        distribution_group: Option<String>,
+        limit: Option<usize>,
+    ) -> Result<Report> {
+        let versions_json =
+            api.latest_version(organization.to_string(), application.to_string())?;
+        let versions = VersionListParser::versions(&versions_json)?;
+        let versions = VersionList::versions_uploaded_since(
+            versions,
+            since,
+            distribution_group,
+            self.version_ordering,
+        );
+
+        let reports = versions
+            .into_iter()
+            .map(|version| {
+                self.crash_list_for_version(
+                    api,
+                    organization.to_string(),
+                    application.to_string(),
+                    version.short_version,
+                    None,
+                    None,
+                )
+            })
+            .collect::<Result<Vec<Report>>>()?;
+
+        let mut report = Report::combined(reports).ok_or(Error::NoVersionFound)?;
+        if let Some(limit) = limit {
+            report.crash_list.crashes.truncate(limit);
+        }
+        Ok(report)
+    }
+
+    /// Fetches the crash groups of a single build of the version.
+    ///
+    /// ```
+    /// # use recrep::api::MockAPI;
+    /// # use recrep::crashes::CrashManager;
+    /// let api = MockAPI::with_two_crashes();
+    /// let manager = CrashManager::default();
+    ///
+    /// let report = manager
+    ///     .crash_list_for_build(&api, "org", "app", "7.41.3".to_string(), "10167".to_string(), None)
+    ///     .unwrap();
+    /// assert_eq!(report.version, "7.41.3");
+    /// assert_eq!(report.build.as_deref(), Some("10167"));
+    /// ```
+    pub fn crash_list_for_build(
+        &self,
+        api: &dyn API,
+        organization: &str,
+        application: &str,
+        version: String,
+        build: String,
+        limit: Option<usize>,
+    ) -> Result<Report> {
+        self.crash_list_for_version(
+            api,
+            organization.to_string(),
+            application.to_string(),
+            version,
+            Some(build),
+            limit,
+        )
+    }
+
+    /// Fetches the crash groups of each of the builds of the version, sending at most
+    /// `parallel_requests` requests at once. The results are in the same order as the builds.
+    ///
+    /// ```
+    /// # use recrep::api::MockAPI;
+    /// # use recrep::crashes::CrashManager;
+    /// let api = MockAPI::with_two_crashes();
+    /// let manager = CrashManager::default();
+    /// let builds = vec!["10166".to_string(), "10167".to_string()];
+    ///
+    /// let reports = manager.crash_lists_for_builds(&api, "org", "app", "7.41.3", &builds, None, 2);
+    /// assert_eq!(reports.len(), 2);
+    /// assert_eq!(reports[1].as_ref().unwrap().build.as_deref(), Some("10167"));
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn crash_lists_for_builds(
+        &self,
+        api: &dyn API,
+        organization: &str,
+        application: &str,
+        version: &str,
+        builds: &[String],
+        limit: Option<usize>,
+        parallel_requests: usize,
+    ) -> Vec<Result<Report>> {
+        fetch_concurrently(builds, parallel_requests, |build| {
+            self.crash_list_for_build(
+                api,
+                organization,
+                application,
+                version.to_string(),
+                build.to_string(),
+                limit,
+            )
+        })
+    }
+
     /// Returns a Report after loading and parsing crashes json from the API
     fn crash_list_for_version(
         &self,
-        api: &impl API,
+        api: &dyn API,
         organization: String,
         application: String,
         version: String,
-    ) -> Result<Report, &'static str> {
-        match api.crashes_json(organization, application, version.clone()) {
-            Ok(json) => Ok(Report::new(
-                version,
-                crash_parsing::crash_list_from_json(json.as_str()).unwrap(),
-            )),
-            Err(_e) => Err("Failed to get crashes json from API."),
+        build: Option<String>,
+        limit: Option<usize>,
+    ) -> Result<Report> {
+        // the limit applies to the error groups in the requested states, so filtering needs all
+        let fetch_limit = if self.states.is_empty() { limit } else { None };
+        let pages = api.crashes_json(
+            organization,
+            application,
+            version.clone(),
+            build.clone(),
+            self.time_window,
+            fetch_limit,
+        )?;
+        let mut crash_list = crash_parsing::crash_list_from_pages(&pages, fetch_limit)?;
+        let reported_period = self.reported_period();
+        for crash in &mut crash_list.crashes {
+            crash.reopened = crash.state() == ErrorGroupState::Closed
+                && crash
+                    .last_occurrence
+                    .as_deref()
+                    .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+                    .is_some_and(|time| reported_period.contains(time.with_timezone(&Utc)));
+        }
+        if !self.states.is_empty() {
+            crash_list
+                .crashes
+                .retain(|crash| self.states.iter().any(|state| crash.has_state(*state)));
+        }
+        if let Some(limit) = limit {
+            crash_list.crashes.truncate(limit);
         }
+        let mut report = Report::new(version, crash_list);
+        report.build = build;
+        Ok(report)
+    }
+
+    /// The period the crashes are counted in: the time window, or the last 30 days AppCenter
+    /// counts by default.
+    fn reported_period(&self) -> TimeWindow {
+        self.time_window
+            .unwrap_or_else(|| TimeWindow::last(TimeDelta::days(30)))
     }
 
     fn crash_list_for_latest_version(
         &self,
-        api: &impl API,
+        api: &dyn API,
         organization: String,
         application: String,
         distribution_group: Option<String>,
-    ) -> Result<Report, &'static str> {
-        let latest_version_json = api
-            .latest_version(organization.to_string(), application.to_string())
-            .expect("Missing version json.");
-        let versions = VersionListParser::versions(&latest_version_json).unwrap();
+        limit: Option<usize>,
+    ) -> Result<Report> {
+        let latest_version_json =
+            api.latest_version(organization.to_string(), application.to_string())?;
+        let versions = VersionListParser::versions(&latest_version_json)?;
         let latest_version = match distribution_group {
-            Some(group) => VersionList::latest_version_of_distribution_group(versions, group),
-            None => VersionList::latest_version(versions),
+            Some(group) => VersionList::latest_version_of_distribution_group(
+                versions,
+                group,
+                self.version_ordering,
+            ),
+            None => VersionList::latest_version(versions, self.version_ordering),
         };
 
         match latest_version {
@@ -86,31 +289,460 @@ impl CrashManager {
                 organization.to_string(),
                 application.to_string(),
                 latest_version.short_version.clone(),
+                None,
+                limit,
             ),
-            None => {
-                Err("💥 Failed to get the latest version. Cannot get crashes without a version.")
-            }
+            None => Err(Error::NoVersionFound),
+        }
+    }
+
+    /// Returns the short versions of the latest `count` releases, newest first, optionally restricted
+    /// to a distribution group.
+    ///
+    /// ```
+    /// # use recrep::api::MockAPI;
+    /// # use recrep::crashes::CrashManager;
+    /// # use recrep::utils::TestHelper;
+    /// let api = MockAPI::with_two_crashes().and_versions_json(TestHelper::fixture(
+    ///     "src/json_parsing/test_fixtures/versions.json",
+    /// ));
+    /// let manager = CrashManager::default();
+    ///
+    /// let versions = manager.latest_versions(&api, "org", "app", 2, None).unwrap();
+    /// assert_eq!(versions, vec!["7.42.0", "7.41.3"]);
+    /// ```
+    pub fn latest_versions(
+        &self,
+        api: &dyn API,
+        organization: &str,
+        application: &str,
+        count: usize,
+        distribution_group: Option<String>,
+    ) -> Result<Vec<String>> {
+        let versions_json =
+            api.latest_version(organization.to_string(), application.to_string())?;
+        let versions = VersionListParser::versions(&versions_json)?;
+        let latest_versions = VersionList::latest_versions(
+            versions,
+            count,
+            distribution_group,
+            self.version_ordering,
+        );
+        if latest_versions.is_empty() {
+            return Err(Error::NoVersionFound);
+        }
+        Ok(latest_versions
+            .into_iter()
+            .map(|version| version.short_version)
+            .collect())
+    }
+
+    /// Fetches the share of users and sessions of the version which were not affected by crashes,
+    /// within the time window if one is set.
+    ///
+    /// ```
+    /// # use recrep::api::MockAPI;
+    /// # use recrep::crashes::CrashManager;
+    /// let api = MockAPI::with_two_crashes().and_statistics();
+    /// let manager = CrashManager::default();
+    ///
+    /// let statistics = manager.crash_free_statistics(&api, "org", "app", "7.41.3").unwrap();
+    /// assert_eq!(statistics.crash_free_users, 99.1234);
+    /// assert_eq!(statistics.crash_free_sessions, Some(99.8));
+    /// ```
+    pub fn crash_free_statistics(
+        &self,
+        api: &dyn API,
+        organization: &str,
+        application: &str,
+        version: &str,
+    ) -> Result<CrashFreeStatistics> {
+        let crash_free_devices = statistics_parsing::crash_free_devices_from_json(
+            &api.crash_free_devices_json(organization, application, version, self.time_window)?,
+        )?;
+        let session_counts = statistics_parsing::session_counts_from_json(
+            &api.session_counts_json(organization, application, version, self.time_window)?,
+        )?;
+        let crash_count = statistics_parsing::crash_count_from_json(&api.crash_counts_json(
+            organization,
+            application,
+            version,
+            self.time_window,
+        )?)?;
+        Ok(CrashFreeStatistics::new(
+            &crash_free_devices,
+            &session_counts,
+            &crash_count,
+        ))
+    }
+
+    /// Returns the builds of the given version, or of the latest version of the distribution group
+    /// if no version is given, newest first.
+    ///
+    /// ```
+    /// # use recrep::api::MockAPI;
+    /// # use recrep::crashes::CrashManager;
+    /// # use recrep::utils::TestHelper;
+    /// let api = MockAPI::with_two_crashes().and_versions_json(TestHelper::fixture(
+    ///     "src/json_parsing/test_fixtures/versions.json",
+    /// ));
+    /// let manager = CrashManager::default();
+    ///
+    /// let builds = manager.builds(&api, "org", "app", Some("7.41.3".to_string()), None).unwrap();
+    /// let builds: Vec<_> = builds.into_iter().filter_map(|version| version.build).collect();
+    /// assert_eq!(builds, vec!["10168", "10167"]);
+    /// ```
+    pub fn builds(
+        &self,
+        api: &dyn API,
+        organization: &str,
+        application: &str,
+        version: Option<String>,
+        distribution_group: Option<String>,
+    ) -> Result<Vec<Version>> {
+        let versions_json =
+            api.latest_version(organization.to_string(), application.to_string())?;
+        let versions = VersionListParser::versions(&versions_json)?;
+        let version = match version {
+            Some(version) => Some(version),
+            None => VersionList::latest_versions(
+                versions.clone(),
+                1,
+                distribution_group,
+                self.version_ordering,
+            )
+            .pop()
+            .map(|version| version.short_version),
+        };
+
+        let builds = match version {
+            Some(version) => VersionList::builds_of_version(versions, &version),
+            None => Vec::new(),
+        };
+        if builds.is_empty() {
+            return Err(Error::NoVersionFound);
         }
+        Ok(builds)
+    }
+
+    /// Fetches the details of all given error groups, sending at most `parallel_requests`
+    /// requests at once. The results are in the same order as the error group ids.
+    ///
+    /// ```
+    /// # use recrep::api::MockAPI;
+    /// # use recrep::crashes::CrashManager;
+    /// # use recrep::utils::TestHelper;
+    /// let api = MockAPI::with_two_crashes().and_operating_systems_json(TestHelper::fixture(
+    ///     "src/json_parsing/test_fixtures/operating_systems.json",
+    /// ));
+    /// let manager = CrashManager::default();
+    /// let ids = vec!["1u".to_string(), "2u".to_string(), "3u".to_string()];
+    ///
+    /// let details = manager.error_groups_details(&api, &ids, "app", "org", 2);
+    /// assert_eq!(details.len(), 3);
+    /// assert!(details.iter().all(|details| details.is_ok()));
+    /// ```
+    pub fn error_groups_details(
+        &self,
+        api: &dyn API,
+        error_group_ids: &[String],
+        application: &str,
+        organization: &str,
+        parallel_requests: usize,
+    ) -> Vec<Result<ErrorGroup>> {
+        fetch_concurrently(error_group_ids, parallel_requests, |error_group_id| {
+            self.error_group_details(api, error_group_id, application, organization)
+        })
     }
 
     pub fn error_group_details(
         &self,
-        api: &impl API,
+        api: &dyn API,
         error_group_id: &str,
         application: &str,
         organization: &str,
-    ) -> Result<ErrorGroup, String> {
-        let response = api.os_versions(organization, application, error_group_id);
-        // json string
-        match response {
-            Ok(response) => {
-                // parsed json structs
-                let error_group =
-                    crash_parsing::error_group_details_from_json(response.as_str()).unwrap();
-                Ok(error_group)
-            }
-
-            Err(e) => Err(format!("Download failure {} for errorGroup", e)),
+    ) -> Result<ErrorGroup> {
+        let response = api.os_versions(organization, application, error_group_id)?;
+        crash_parsing::error_group_details_from_json(response.as_str())
+    }
+
+    /// Fetches the stack traces of all given error groups, sending at most `parallel_requests`
+    /// requests at once. The results are in the same order as the error group ids.
+    ///
+    /// ```
+    /// # use recrep::api::MockAPI;
+    /// # use recrep::crashes::CrashManager;
+    /// let api = MockAPI::with_two_crashes().and_stacktrace();
+    /// let manager = CrashManager::default();
+    /// let ids = vec!["1u".to_string(), "2u".to_string()];
+    ///
+    /// let stacktraces = manager.stacktraces(&api, &ids, "app", "org", 2);
+    /// assert_eq!(stacktraces.len(), 2);
+    /// assert!(stacktraces.iter().all(|stacktrace| stacktrace.is_ok()));
+    /// ```
+    pub fn stacktraces(
+        &self,
+        api: &dyn API,
+        error_group_ids: &[String],
+        application: &str,
+        organization: &str,
+        parallel_requests: usize,
+    ) -> Vec<Result<StackTrace>> {
+        fetch_concurrently(error_group_ids, parallel_requests, |error_group_id| {
+            let response = api.stacktrace_json(organization, application, error_group_id)?;
+            crash_parsing::stacktrace_from_json(response.as_str())
+        })
+    }
+}
+
+/// Calls `fetch` for every id, e.g. error group ids or builds, on at most `parallel_requests`
+/// threads. The results are in the same order as the ids.
+fn fetch_concurrently<T, F>(ids: &[String], parallel_requests: usize, fetch: F) -> Vec<Result<T>>
+where
+    T: Send,
+    F: Fn(&str) -> Result<T> + Sync,
+{
+    let next_index = AtomicUsize::new(0);
+    let workers = parallel_requests.max(1).min(ids.len());
+
+    let mut results: Vec<(usize, Result<T>)> = thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut fetched = Vec::new();
+                    loop {
+                        let index = next_index.fetch_add(1, Ordering::SeqCst);
+                        let id = match ids.get(index) {
+                            Some(id) => id,
+                            None => return fetched,
+                        };
+                        fetched.push((index, fetch(id)));
+                    }
+                })
+            })
+            .collect();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("Fetching concurrently panicked"))
+            .collect()
+    });
+
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::MockAPI;
+    use crate::utils::TestHelper;
+    use crate::Error;
+    use std::time::Duration;
+
+    /// Answers each error group with its numeric id as error count. Lower ids take longer, so
+    /// the responses arrive in reverse order.
+    struct EchoAPI {}
+
+    impl API for EchoAPI {
+        fn new(_token: String) -> Self {
+            EchoAPI {}
+        }
+
+        fn latest_version(&self, _organization: String, _application: String) -> Result<String> {
+            Err(Error::NotFound)
+        }
+
+        fn crashes_json(
+            &self,
+            _organization: String,
+            _application: String,
+            _version: String,
+            _build: Option<String>,
+            _time_window: Option<TimeWindow>,
+            _limit: Option<usize>,
+        ) -> Result<Vec<String>> {
+            Err(Error::NotFound)
+        }
+
+        fn os_versions(
+            &self,
+            _organization: &str,
+            _application: &str,
+            error_group_id: &str,
+        ) -> Result<String> {
+            let id: u64 = error_group_id.trim_end_matches('u').parse().unwrap();
+            thread::sleep(Duration::from_millis(10 * (5 - id)));
+            Ok(format!(
+                r#"{{"errorCount": {}, "operatingSystems": []}}"#,
+                id
+            ))
+        }
+
+        fn stacktrace_json(
+            &self,
+            _organization: &str,
+            _application: &str,
+            _error_group_id: &str,
+        ) -> Result<String> {
+            Err(Error::NotFound)
         }
+
+        fn crash_free_devices_json(
+            &self,
+            _organization: &str,
+            _application: &str,
+            _version: &str,
+            _time_window: Option<TimeWindow>,
+        ) -> Result<String> {
+            Err(Error::NotFound)
+        }
+
+        fn session_counts_json(
+            &self,
+            _organization: &str,
+            _application: &str,
+            _version: &str,
+            _time_window: Option<TimeWindow>,
+        ) -> Result<String> {
+            Err(Error::NotFound)
+        }
+
+        fn crash_counts_json(
+            &self,
+            _organization: &str,
+            _application: &str,
+            _version: &str,
+            _time_window: Option<TimeWindow>,
+        ) -> Result<String> {
+            Err(Error::NotFound)
+        }
+    }
+
+    #[test]
+    fn error_groups_are_filtered_by_state_before_the_limit() {
+        let json = TestHelper::fixture("src/json_parsing/test_fixtures/two_crashes.json").replacen(
+            r#""state": "Open""#,
+            r#""state": "Closed""#,
+            1,
+        );
+        let manager = CrashManager {
+            states: vec![ErrorGroupState::Open],
+            ..CrashManager::default()
+        };
+
+        let report = manager
+            .crash_list(
+                &MockAPI::with_crashes_json(json),
+                "org",
+                "app",
+                Some("7.41.3".to_string()),
+                None,
+                Some(1),
+            )
+            .unwrap();
+
+        assert_eq!(report.crash_list.crashes.len(), 1);
+        assert_eq!(
+            report.crash_list.crashes[0].error_group_id.as_deref(),
+            Some("1549869227u")
+        );
+    }
+
+    #[test]
+    fn closed_error_groups_keep_their_state() {
+        let json = TestHelper::fixture("src/json_parsing/test_fixtures/two_crashes.json").replacen(
+            r#""state": "Open""#,
+            r#""state": "Closed""#,
+            1,
+        );
+        let manager = CrashManager {
+            states: vec![ErrorGroupState::Closed],
+            ..CrashManager::default()
+        };
+
+        let report = manager
+            .crash_list(
+                &MockAPI::with_crashes_json(json),
+                "org",
+                "app",
+                Some("7.41.3".to_string()),
+                None,
+                None,
+            )
+            .unwrap();
+
+        assert_eq!(report.crash_list.crashes.len(), 1);
+        assert_eq!(
+            report.crash_list.crashes[0].state,
+            Some(ErrorGroupState::Closed)
+        );
+    }
+
+    #[test]
+    fn closed_error_groups_crashing_within_the_period_are_reopened() {
+        let api = MockAPI::with_crashes_json(TestHelper::fixture(
+            "src/json_parsing/test_fixtures/closed_crashes.json",
+        ));
+        let manager = CrashManager {
+            time_window: Some(TimeWindow::new(
+                "2019-05-15T00:00:00Z".parse().unwrap(),
+                "2019-05-20T00:00:00Z".parse().unwrap(),
+            )),
+            states: vec![ErrorGroupState::Open, ErrorGroupState::Reopened],
+            ..CrashManager::default()
+        };
+
+        let report = manager
+            .crash_list(&api, "org", "app", Some("7.41.3".to_string()), None, None)
+            .unwrap();
+
+        let crashes = &report.crash_list.crashes;
+        assert_eq!(crashes.len(), 2);
+        assert_eq!(crashes[0].error_group_id.as_deref(), Some("92620314u"));
+        assert!(crashes[0].reopened);
+        assert_eq!(crashes[0].state, Some(ErrorGroupState::Closed));
+        assert_eq!(crashes[1].error_group_id.as_deref(), Some("1549869227u"));
+        assert!(!crashes[1].reopened);
+    }
+
+    #[test]
+    fn closed_error_groups_without_recent_crashes_stay_closed() {
+        let api = MockAPI::with_crashes_json(TestHelper::fixture(
+            "src/json_parsing/test_fixtures/closed_crashes.json",
+        ));
+        let manager = CrashManager {
+            time_window: Some(TimeWindow::new(
+                "2019-05-15T00:00:00Z".parse().unwrap(),
+                "2019-05-20T00:00:00Z".parse().unwrap(),
+            )),
+            states: vec![ErrorGroupState::Closed],
+            ..CrashManager::default()
+        };
+
+        let report = manager
+            .crash_list(&api, "org", "app", Some("7.41.3".to_string()), None, None)
+            .unwrap();
+
+        let reopened: Vec<bool> = report
+            .crash_list
+            .crashes
+            .iter()
+            .map(|crash| crash.reopened)
+            .collect();
+        assert_eq!(reopened, vec![true, false]);
+    }
+
+    #[test]
+    fn error_groups_details_keep_the_order_of_the_ids() {
+        let ids: Vec<String> = (0..5).map(|id| format!("{}u", id)).collect();
+        let manager = CrashManager::default();
+
+        let details = manager.error_groups_details(&EchoAPI {}, &ids, "app", "org", 3);
+        let counts: Vec<u64> = details
+            .into_iter()
+            .map(|details| details.unwrap().count)
+            .collect();
+        assert_eq!(counts, vec![0, 1, 2, 3, 4]);
     }
 }
@@ -0,0 +1,272 @@
+use crate::error::{Error, Result};
+use crate::model::ReportedCrash;
+use std::str::FromStr;
+
+/// A column of the CSV and TSV formats, holding one field of each reported crash.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsvColumn {
+    ErrorGroupId,
+    Count,
+    DeviceCount,
+    AppVersion,
+    AppBuild,
+    FirstOccurrence,
+    LastOccurrence,
+    State,
+    Reopened,
+    ExceptionType,
+    ExceptionMessage,
+    ExceptionFile,
+    ExceptionClassName,
+    ExceptionMethod,
+    ExceptionAppCode,
+    CodeRaw,
+    ThresholdPercentage,
+    ThresholdExceeded,
+    /// The affected operating systems as `name: count` pairs, separated by `; `.
+    OperatingSystems,
+    /// The occurrences per build as `build: count` pairs, separated by `; `.
+    Builds,
+    /// The occurrences per version as `version: count` pairs, separated by `; `.
+    Versions,
+}
+
+impl CsvColumn {
+    /// All columns, in the order they are written by default.
+    pub const ALL: [CsvColumn; 21] = [
+        CsvColumn::ErrorGroupId,
+        CsvColumn::Count,
+        CsvColumn::DeviceCount,
+        CsvColumn::AppVersion,
+        CsvColumn::AppBuild,
+        CsvColumn::FirstOccurrence,
+        CsvColumn::LastOccurrence,
+        CsvColumn::State,
+        CsvColumn::Reopened,
+        CsvColumn::ExceptionType,
+        CsvColumn::ExceptionMessage,
+        CsvColumn::ExceptionFile,
+        CsvColumn::ExceptionClassName,
+        CsvColumn::ExceptionMethod,
+        CsvColumn::ExceptionAppCode,
+        CsvColumn::CodeRaw,
+        CsvColumn::ThresholdPercentage,
+        CsvColumn::ThresholdExceeded,
+        CsvColumn::OperatingSystems,
+        CsvColumn::Builds,
+        CsvColumn::Versions,
+    ];
+
+    /// The name of the column, as used in the header row and on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            CsvColumn::ErrorGroupId => "error_group_id",
+            CsvColumn::Count => "count",
+            CsvColumn::DeviceCount => "device_count",
+            CsvColumn::AppVersion => "app_version",
+            CsvColumn::AppBuild => "app_build",
+            CsvColumn::FirstOccurrence => "first_occurrence",
+            CsvColumn::LastOccurrence => "last_occurrence",
+            CsvColumn::State => "state",
+            CsvColumn::Reopened => "reopened",
+            CsvColumn::ExceptionType => "exception_type",
+            CsvColumn::ExceptionMessage => "exception_message",
+            CsvColumn::ExceptionFile => "exception_file",
+            CsvColumn::ExceptionClassName => "exception_class_name",
+            CsvColumn::ExceptionMethod => "exception_method",
+            CsvColumn::ExceptionAppCode => "exception_app_code",
+            CsvColumn::CodeRaw => "code_raw",
+            CsvColumn::ThresholdPercentage => "threshold_percentage",
+            CsvColumn::ThresholdExceeded => "threshold_exceeded",
+            CsvColumn::OperatingSystems => "operating_systems",
+            CsvColumn::Builds => "builds",
+            CsvColumn::Versions => "versions",
+        }
+    }
+
+    /// The field of the crash this column holds. Missing fields are empty.
+    fn value(self, crash: &ReportedCrash) -> String {
+        let text = |value: &Option<String>| value.clone().unwrap_or_default();
+        let pairs = |pairs: Vec<(String, u64)>| {
+            pairs
+                .iter()
+                .map(|(name, count)| format!("{}: {}", name, count))
+                .collect::<Vec<String>>()
+                .join("; ")
+        };
+
+        match self {
+            CsvColumn::ErrorGroupId => text(&crash.error_group_id),
+            CsvColumn::Count => crash.count.to_string(),
+            CsvColumn::DeviceCount => crash.device_count.to_string(),
+            CsvColumn::AppVersion => text(&crash.app_version),
+            CsvColumn::AppBuild => text(&crash.app_build),
+            CsvColumn::FirstOccurrence => text(&crash.first_occurrence),
+            CsvColumn::LastOccurrence => text(&crash.last_occurrence),
+            CsvColumn::State => crash.state.to_string(),
+            CsvColumn::Reopened => crash.reopened.to_string(),
+            CsvColumn::ExceptionType => text(&crash.exception_type),
+            CsvColumn::ExceptionMessage => text(&crash.exception_message),
+            CsvColumn::ExceptionFile => text(&crash.exception_file),
+            CsvColumn::ExceptionClassName => text(&crash.exception_class_name),
+            CsvColumn::ExceptionMethod => text(&crash.exception_method),
+            CsvColumn::ExceptionAppCode => crash
+                .exception_app_code
+                .map(|app_code| app_code.to_string())
+                .unwrap_or_default(),
+            CsvColumn::CodeRaw => text(&crash.code_raw),
+            CsvColumn::ThresholdPercentage => crash
+                .threshold_percentage
+                .map(|percentage| format!("{:.2}", percentage))
+                .unwrap_or_default(),
+            CsvColumn::ThresholdExceeded => crash.threshold_exceeded.to_string(),
+            CsvColumn::OperatingSystems => pairs(
+                crash
+                    .operating_systems
+                    .iter()
+                    .map(|os| (os.name.clone(), os.count))
+                    .collect(),
+            ),
+            CsvColumn::Builds => pairs(
+                crash
+                    .builds
+                    .iter()
+                    .map(|build| (build.build.clone(), build.count))
+                    .collect(),
+            ),
+            CsvColumn::Versions => pairs(
+                crash
+                    .versions
+                    .iter()
+                    .map(|version| (version.version.clone(), version.count))
+                    .collect(),
+            ),
+        }
+    }
+}
+
+impl FromStr for CsvColumn {
+    type Err = Error;
+
+    /// Parses the name of a column as given on the command line.
+    ///
+    /// ```
+    /// use recrep::CsvColumn;
+    ///
+    /// assert_eq!("device_count".parse::<CsvColumn>().unwrap(), CsvColumn::DeviceCount);
+    /// assert!("devices".parse::<CsvColumn>().is_err());
+    /// ```
+    fn from_str(name: &str) -> Result<CsvColumn> {
+        CsvColumn::ALL
+            .iter()
+            .copied()
+            .find(|column| column.name() == name)
+            .ok_or(Error::InvalidOptions(
+                "Unknown column, see `--help` for the available columns.",
+            ))
+    }
+}
+
+/// Writes a header row and one row per crash, separating the fields with the delimiter. Fields
+/// containing the delimiter, quotes or line breaks are quoted, fields a spreadsheet would evaluate
+/// as formula are prefixed with `'`.
+///
+/// ```
+/// use recrep::model::ReportedCrash;
+/// use recrep::{csv_column, CsvColumn};
+///
+/// let crash = ReportedCrash {
+///     error_group_id: Some("1u".to_string()),
+///     exception_message: Some("a \"quoted\", message".to_string()),
+///     exception_method: Some("=HYPERLINK()".to_string()),
+///     ..ReportedCrash::default()
+/// };
+///
+/// let csv = csv_column::render(
+///     &[crash],
+///     &[CsvColumn::ErrorGroupId, CsvColumn::ExceptionMessage, CsvColumn::ExceptionMethod],
+///     ',',
+/// );
+/// assert_eq!(
+///     csv,
+///     "error_group_id,exception_message,exception_method\n\
+///      1u,\"a \"\"quoted\"\", message\",'=HYPERLINK()\n"
+/// );
+/// ```
+pub fn render(crashes: &[ReportedCrash], columns: &[CsvColumn], delimiter: char) -> String {
+    let row = |fields: Vec<String>| {
+        let fields: Vec<String> = fields
+            .iter()
+            .map(|field| quote(&defuse_formula(field), delimiter))
+            .collect();
+        fields.join(&delimiter.to_string()) + "\n"
+    };
+
+    let mut rendered = row(columns
+        .iter()
+        .map(|column| column.name().to_string())
+        .collect());
+    for crash in crashes {
+        rendered += &row(columns.iter().map(|column| column.value(crash)).collect());
+    }
+    rendered
+}
+
+/// Prefixes fields starting like a formula with `'`, so spreadsheets show them as text instead
+/// of evaluating them.
+fn defuse_formula(field: &str) -> String {
+    if field.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    }
+}
+
+/// Quotes the field if it contains the delimiter, quotes or line breaks, doubling its quotes.
+fn quote(field: &str, delimiter: char) -> String {
+    if field.contains([delimiter, '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tabs_are_quoted_in_tsv_only() {
+        assert_eq!(quote("a\tb", '\t'), "\"a\tb\"");
+        assert_eq!(quote("a\tb", ','), "a\tb");
+        assert_eq!(quote("a,b", '\t'), "a,b");
+    }
+
+    #[test]
+    fn formulas_are_prefixed() {
+        assert_eq!(defuse_formula("=1+1"), "'=1+1");
+        assert_eq!(defuse_formula("+1"), "'+1");
+        assert_eq!(
+            defuse_formula("-[NSArray objectAtIndex:]"),
+            "'-[NSArray objectAtIndex:]"
+        );
+        assert_eq!(defuse_formula("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(defuse_formula("a=b"), "a=b");
+    }
+
+    #[test]
+    fn states_are_written_by_name() {
+        let crash = ReportedCrash {
+            state: crate::model::ErrorGroupState::Ignored,
+            ..ReportedCrash::default()
+        };
+        assert_eq!(CsvColumn::State.value(&crash), "Ignored");
+    }
+
+    #[test]
+    fn every_column_has_a_unique_name() {
+        for column in CsvColumn::ALL.iter() {
+            assert_eq!(column.name().parse::<CsvColumn>().unwrap(), *column);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// A specialized `Result` type for recrep operations.
+pub type Result<T> = std::result::Result<T, Error>;
+
+/// The errors that can occur while fetching, parsing and reporting crashes.
+#[derive(Debug)]
+pub enum Error {
+    /// The API token was rejected (HTTP 401).
+    Unauthorized,
+
+    /// The organization, application or error group is unknown (HTTP 404).
+    NotFound,
+
+    /// AppCenter throttled the requests (HTTP 429). Contains the delay requested by the
+    /// `Retry-After` header, if any.
+    Throttled { retry_after: Option<Duration> },
+
+    /// Any other unsuccessful HTTP status.
+    Http { status: u16, body: String },
+
+    /// The request could not be sent or the response could not be read.
+    Transport(String),
+
+    /// The response did not match the expected json schema.
+    Json { path: String, message: String },
+
+    /// No version matched the given criteria, so there is nothing to report on.
+    NoVersionFound,
+
+    /// The report could not be written.
+    Io(std::io::Error),
+
+    /// A replayed request was not recorded. Contains the path the recording is expected at.
+    MissingRecording(PathBuf),
+
+    /// The options of the crash reporter contradict each other or are out of range.
+    InvalidOptions(&'static str),
+
+    /// A template or partial failed to compile or to render. Contains the error of Handlebars,
+    /// pointing at the template and line.
+    Template(String),
+}
+
+impl Error {
+    /// Maps an unsuccessful HTTP status to an error.
+    ///
+    /// ```
+    /// use recrep::Error;
+    ///
+    /// assert!(matches!(Error::from_status(401, String::new()), Error::Unauthorized));
+    /// assert!(matches!(Error::from_status(503, String::new()), Error::Http { status: 503, .. }));
+    /// ```
+    pub fn from_status(status: u16, body: String) -> Error {
+        match status {
+            401 => Error::Unauthorized,
+            404 => Error::NotFound,
+            429 => Error::Throttled { retry_after: None },
+            _ => Error::Http { status, body },
+        }
+    }
+}
+
+impl Error {
+    /// Whether the failed request may succeed when sent again. This is the case for throttled
+    /// requests, server errors and network failures.
+    ///
+    /// ```
+    /// use recrep::Error;
+    ///
+    /// assert!(Error::from_status(429, String::new()).is_transient());
+    /// assert!(Error::from_status(503, String::new()).is_transient());
+    /// assert!(!Error::from_status(401, String::new()).is_transient());
+    /// ```
+    pub fn is_transient(&self) -> bool {
+        match self {
+            Error::Throttled { .. } | Error::Transport(_) => true,
+            Error::Http { status, .. } => *status >= 500,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Unauthorized => write!(f, "🔑 AppCenter rejected the API token."),
+            Error::NotFound => write!(
+                f,
+                "💥 AppCenter does not know the given organization, application or error group."
+            ),
+            Error::Throttled { .. } => write!(f, "🐌 AppCenter throttled the requests."),
+            Error::Http { status, body } => {
+                write!(f, "💥 AppCenter responded with HTTP {}: {}", status, body)
+            }
+            Error::Transport(message) => write!(f, "💥 Failed to talk to AppCenter: {}", message),
+            Error::Json { path, message } => {
+                write!(f, "💥 Failed to parse json at `{}`: {}", path, message)
+            }
+            Error::NoVersionFound => write!(
+                f,
+                "💥 Failed to get the latest version. Cannot get crashes without a version."
+            ),
+            Error::Io(e) => write!(f, "💥 Failed to write the report: {}", e),
+            Error::MissingRecording(path) => {
+                write!(f, "📼 There is no recording at {}.", path.display())
+            }
+            Error::InvalidOptions(message) => write!(f, "💥 Invalid options: {}", message),
+            Error::Template(message) => write!(f, "📝 Invalid template: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl From<handlebars::TemplateError> for Error {
+    fn from(error: handlebars::TemplateError) -> Self {
+        Error::Template(error.to_string().trim_end().to_string())
+    }
+}
+
+impl From<handlebars::RenderError> for Error {
+    fn from(error: handlebars::RenderError) -> Self {
+        Error::Template(error.to_string())
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for Error {
+    fn from(error: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        Error::Json {
+            path: error.path().to_string(),
+            message: error.into_inner().to_string(),
+        }
+    }
+}
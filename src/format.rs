@@ -0,0 +1,51 @@
+use crate::error::{Error, Result};
+use std::str::FromStr;
+
+/// The formats a report can be written in.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Format {
+    /// Plain text meant to be read by people, e.g. as newsletter.
+    #[default]
+    Text,
+
+    /// Markdown with tables and collapsible sections, e.g. for wiki pages.
+    Markdown,
+
+    /// A self-contained HTML page, e.g. for HTML mails.
+    Html,
+
+    /// Json meant to be processed by other tools.
+    Json,
+
+    /// Comma separated values with one row per crash, e.g. for spreadsheets.
+    Csv,
+
+    /// Tab separated values with one row per crash, e.g. for spreadsheets.
+    Tsv,
+}
+
+impl FromStr for Format {
+    type Err = Error;
+
+    /// Parses the name of a format as given on the command line.
+    ///
+    /// ```
+    /// use recrep::Format;
+    ///
+    /// assert_eq!("json".parse::<Format>().unwrap(), Format::Json);
+    /// assert!("yaml".parse::<Format>().is_err());
+    /// ```
+    fn from_str(format: &str) -> Result<Format> {
+        match format {
+            "text" => Ok(Format::Text),
+            "markdown" => Ok(Format::Markdown),
+            "html" => Ok(Format::Html),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "tsv" => Ok(Format::Tsv),
+            _ => Err(Error::InvalidOptions(
+                "The format must be one of `text`, `markdown`, `html`, `json`, `csv` or `tsv`.",
+            )),
+        }
+    }
+}
@@ -1,6 +1,7 @@
+use crate::error::Result;
 use crate::model::CrashList;
 use crate::model::ErrorGroup;
-use serde_json;
+use crate::model::StackTrace;
 
 ///
 /// Parses a CrashList from the given json
@@ -19,24 +20,102 @@ use serde_json;
 ///     .expect("Failed to parse crash test fixture");
 /// assert_eq!(crash_list.crashes.len(), 30);
 /// ```
-pub fn crash_list_from_json(json: &str) -> Result<CrashList, &'static str> {
-    let crash_list: serde_json::Result<CrashList> = serde_json::from_str(json);
-    match crash_list {
-        Ok(crash_list) => Ok(crash_list),
-        Err(e) => {
-            println!("Parsing error: {:}", e);
-            Err("💥 Failed to parse json into crash list. This happens when there is no app for the given organization, name and version.")
+///
+/// Schema mismatches report the path of the offending element:
+///
+/// ```
+/// use recrep::json_parsing::crash_parsing;
+/// use recrep::Error;
+///
+/// let json = r#"{"errorGroups": [{"count": "many"}]}"#;
+/// match crash_parsing::crash_list_from_json(json) {
+///     Err(Error::Json { path, .. }) => assert_eq!(path, "errorGroups[0].count"),
+///     _ => panic!("Expected a json error"),
+/// }
+/// ```
+///
+/// Reason frames without app code marker take the one of the error group:
+///
+/// ```
+/// use recrep::json_parsing::crash_parsing;
+///
+/// let json = r#"{"errorGroups": [{"exceptionAppCode": true, "reasonFrame": {"method": "codes"}}]}"#;
+/// let crash_list = crash_parsing::crash_list_from_json(json).unwrap();
+/// let reason_frame = crash_list.crashes[0].reason_frame.as_ref().unwrap();
+/// assert_eq!(reason_frame.app_code, Some(true));
+/// ```
+pub fn crash_list_from_json(json: &str) -> Result<CrashList> {
+    let mut crash_list: CrashList = super::from_json(json)?;
+    for crash in &mut crash_list.crashes {
+        if let Some(reason_frame) = crash.reason_frame.as_mut() {
+            reason_frame.app_code = reason_frame.app_code.or(crash.exception_app_code);
         }
     }
+    Ok(crash_list)
 }
 
-pub fn error_group_details_from_json(json: &str) -> Result<ErrorGroup, &'static str> {
-    let error_group_list: serde_json::Result<ErrorGroup> = serde_json::from_str(json);
-    match error_group_list {
-        Ok(list) => Ok(list),
-        Err(e) => {
-            println!("Parsing error: {:}", e);
-            Err("💥 Failed to parse json into errorGroup list.")
-        }
+///
+/// Parses every page of error groups and merges them into one CrashList. When a limit is given,
+/// only the first `limit` crashes are kept.
+///
+/// ```
+/// # use std::fs::File;
+/// # use std::io::prelude::*;
+/// # use std::path::Path;
+/// use recrep::json_parsing::crash_parsing;
+/// # let read = |path: &str| {
+/// #     let mut file = File::open(Path::new(path)).expect("Unable to open test fixture");
+/// #     let mut json = String::new();
+/// #     file.read_to_string(&mut json).expect("Failed to read fixture file into string");
+/// #     json
+/// # };
+/// let pages = vec![
+///     read("src/json_parsing/test_fixtures/paginated_crashes_page_1.json"),
+///     read("src/json_parsing/test_fixtures/paginated_crashes_page_2.json"),
+/// ];
+/// let crash_list = crash_parsing::crash_list_from_pages(&pages, None)
+///     .expect("Failed to parse crash test fixtures");
+/// assert_eq!(crash_list.crashes.len(), 5);
+/// assert_eq!(crash_list.next_link, None);
+///
+/// let crash_list = crash_parsing::crash_list_from_pages(&pages, Some(4))
+///     .expect("Failed to parse crash test fixtures");
+/// assert_eq!(crash_list.crashes.len(), 4);
+/// ```
+pub fn crash_list_from_pages(pages: &[String], limit: Option<usize>) -> Result<CrashList> {
+    let mut merged = CrashList {
+        crashes: Vec::new(),
+        next_link: None,
+    };
+
+    for page in pages {
+        let crash_list = crash_list_from_json(page)?;
+        merged.crashes.extend(crash_list.crashes);
+        merged.next_link = crash_list.next_link;
     }
+
+    if let Some(limit) = limit {
+        merged.crashes.truncate(limit);
+    }
+
+    Ok(merged)
+}
+
+pub fn error_group_details_from_json(json: &str) -> Result<ErrorGroup> {
+    super::from_json(json)
+}
+
+/// Parses the stack trace of an error group.
+///
+/// ```
+/// use recrep::json_parsing::crash_parsing;
+/// use recrep::utils::TestHelper;
+///
+/// let json = TestHelper::fixture("src/json_parsing/test_fixtures/stacktrace.json");
+/// let stacktrace = crash_parsing::stacktrace_from_json(&json).unwrap();
+/// assert_eq!(stacktrace.threads.len(), 1);
+/// assert_eq!(stacktrace.frames().len(), 5);
+/// ```
+pub fn stacktrace_from_json(json: &str) -> Result<StackTrace> {
+    super::from_json(json)
 }
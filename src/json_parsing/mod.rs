@@ -1,4 +1,14 @@
 pub mod crash_parsing;
+pub mod statistics_parsing;
 
 pub use self::version_parsing::VersionListParser;
 pub mod version_parsing;
+
+use crate::error::Result;
+use serde::de::DeserializeOwned;
+
+/// Deserializes the json into `T`, reporting the path of the offending element on failure.
+pub(crate) fn from_json<T: DeserializeOwned>(json: &str) -> Result<T> {
+    let deserializer = &mut serde_json::Deserializer::from_str(json);
+    Ok(serde_path_to_error::deserialize(deserializer)?)
+}
@@ -0,0 +1,44 @@
+use crate::error::Result;
+use crate::model::{CrashCount, CrashFreeDevices, SessionCount};
+
+/// Parses the average share of devices without crashes.
+///
+/// ```
+/// use recrep::json_parsing::statistics_parsing;
+/// use recrep::utils::TestHelper;
+///
+/// let json = TestHelper::fixture("src/json_parsing/test_fixtures/crash_free_devices.json");
+/// let crash_free_devices = statistics_parsing::crash_free_devices_from_json(&json).unwrap();
+/// assert_eq!(crash_free_devices.average_percentage, 99.1234);
+/// ```
+pub fn crash_free_devices_from_json(json: &str) -> Result<CrashFreeDevices> {
+    super::from_json(json)
+}
+
+/// Parses the daily session counts.
+///
+/// ```
+/// use recrep::json_parsing::statistics_parsing;
+/// use recrep::utils::TestHelper;
+///
+/// let json = TestHelper::fixture("src/json_parsing/test_fixtures/session_counts.json");
+/// let session_counts = statistics_parsing::session_counts_from_json(&json).unwrap();
+/// assert_eq!(session_counts.len(), 2);
+/// ```
+pub fn session_counts_from_json(json: &str) -> Result<Vec<SessionCount>> {
+    super::from_json(json)
+}
+
+/// Parses the amount of crashes.
+///
+/// ```
+/// use recrep::json_parsing::statistics_parsing;
+/// use recrep::utils::TestHelper;
+///
+/// let json = TestHelper::fixture("src/json_parsing/test_fixtures/crash_counts.json");
+/// let crash_count = statistics_parsing::crash_count_from_json(&json).unwrap();
+/// assert_eq!(crash_count.count, 50);
+/// ```
+pub fn crash_count_from_json(json: &str) -> Result<CrashCount> {
+    super::from_json(json)
+}
@@ -1,7 +1,12 @@
 pub mod api;
+mod builder;
 pub mod crashes;
+pub mod csv_column;
+pub mod error;
+mod format;
 pub mod json_parsing;
 pub mod model;
+mod template_helpers;
 pub mod utils;
 
 #[macro_use]
@@ -9,11 +14,20 @@ extern crate serde_json;
 extern crate handlebars;
 extern crate serde;
 
-use crate::model::{OperatingSystemCount, Report};
-use api::{AppCenter, API};
+pub use crate::builder::CrashReporterBuilder;
+pub use crate::csv_column::CsvColumn;
+pub use crate::error::{Error, Result};
+pub use crate::format::Format;
+use crate::model::{
+    BuildCount, Crash, CrashFreeStatistics, ErrorGroupState, OperatingSystemCount, Report,
+    ReportDocument, ReportFilters, ReportThresholds, ReportedCrash, ReportedDiff, StackFrame,
+    TimeWindow, Trend, Version, VersionDiff, VersionOrdering, REPORT_SCHEMA_VERSION,
+};
+use api::API;
+use chrono::{DateTime, Utc};
 use handlebars::Handlebars;
 use std::collections::HashMap;
-use utils::{FileWriter, Printing, StdOutPrinter, Writing};
+use utils::{Printing, Writing};
 
 /// The `CrashReporter` is the heart of `recrep`.
 pub struct CrashReporter {
@@ -21,65 +35,134 @@ pub struct CrashReporter {
     organization: String,
     application: String,
     version: Option<String>,
+    build: Option<String>,
+    since: Option<DateTime<Utc>>,
+    time_window: Option<TimeWindow>,
     distribution_group: Option<String>,
     threshold: Option<u64>,
+    min_crash_free_users: Option<f64>,
+    min_crash_free_sessions: Option<f64>,
     use_arithmetic_mean: bool,
     show_os_information: bool,
+    stacktrace_frames: Option<usize>,
+    break_down_by_build: bool,
     filter_out_errors: bool,
+    states: Vec<ErrorGroupState>,
+    error_group_limit: Option<usize>,
+    parallel_requests: usize,
+    base_version: Option<String>,
+    regression_threshold: f64,
+    version_ordering: VersionOrdering,
+    format: Format,
+    columns: Vec<CsvColumn>,
+    template: Option<String>,
+    empty_template: Option<String>,
+    partials: Vec<(String, String)>,
+    /// The compiled templates of the text and Markdown formats, which aren't escaped.
+    templates: Handlebars,
+    /// The compiled templates of the HTML format, which escape HTML.
+    html_templates: Handlebars,
+    api: Box<dyn API>,
     file_writer: &'static dyn Writing,
     printer: &'static dyn Printing,
 }
 
 impl CrashReporter {
     ///
-    /// Create a crash reporter
+    /// Create a builder for a crash reporter
     ///
     ///```
     /// use recrep::CrashReporter;
     ///
-    /// let reporter = CrashReporter::with_token("abc", "org", "app", Some("1.2.3".to_string()),
-    /// Some("My-Distribution-Group".to_string()), None, false, false, false);
+    /// let reporter = CrashReporter::builder("abc", "org", "app")
+    ///     .version("1.2.3")
+    ///     .distribution_group("My-Distribution-Group")
+    ///     .build()
+    ///     .unwrap();
     ///
     /// assert_eq!("abc", reporter.token);
     /// ```
-    pub fn with_token(
-        token: &str,
-        organization: &str,
-        application: &str,
-        version: Option<String>,
-        distribution_group: Option<String>,
-        threshold: Option<u64>,
-        use_arithmetic_mean: bool,
-        show_os_information: bool,
-        filter_out_errors: bool,
-    ) -> CrashReporter {
-        CrashReporter {
-            token: token.to_string(),
-            organization: organization.to_string(),
-            application: application.to_string(),
-            version: version.map(|s| s.to_string()),
-            file_writer: &FileWriter {},
-            printer: &StdOutPrinter {},
-            distribution_group: distribution_group,
-            threshold: threshold,
-            use_arithmetic_mean: use_arithmetic_mean,
-            show_os_information: show_os_information,
-            filter_out_errors: filter_out_errors,
-        }
-    }
-
-    pub fn create_report(&self, outfile: Option<&str>) {
-        match self.crashes_from_app_center() {
-            Ok(mut crash_report) => {
-                if self.show_os_information {
-                    let error_groups = self.download_group_details_for_crashes(&crash_report);
-                    crash_report.assign_operating_system_details(error_groups);
-                }
+    pub fn builder(token: &str, organization: &str, application: &str) -> CrashReporterBuilder {
+        CrashReporterBuilder::new(token, organization, application)
+    }
 
-                self.write_report(crash_report, outfile)
-            }
-            Err(x) => println!("Failed to get list of crashes with error: {:}", x),
-        }
+    /// Fetches the crashes and writes the report. Returns the amount of reported crashes exceeding
+    /// the threshold plus the amount of crash-free rates below their minimum. Fails when the
+    /// crashes can't be fetched or the report can't be written.
+    pub fn create_report(&self, outfile: Option<&str>) -> Result<usize> {
+        let crash_report = self.fetch_report()?;
+        let threshold_violations = self.crashes_exceeding_threshold(&crash_report)
+            + self.crash_free_rates_below_minimum(&crash_report);
+        self.write_report(crash_report, outfile)?;
+        Ok(threshold_violations)
+    }
+
+    /// Counts the crash-free rates of the report which fall below their configured minimum. A
+    /// rate AppCenter doesn't know counts as below the minimum.
+    ///
+    /// ```
+    /// # use recrep::api::MockAPI;
+    /// # use recrep::CrashReporter;
+    /// #
+    /// let reporter = CrashReporter::builder("abc", "org", "app")
+    ///     .version("7.41.3")
+    ///     .min_crash_free_users(99.5)
+    ///     .min_crash_free_sessions(99.5)
+    ///     .api(Box::new(MockAPI::with_two_crashes().and_statistics()))
+    ///     .build()
+    ///     .unwrap();
+    /// let report = reporter.fetch_report().unwrap();
+    /// assert_eq!(reporter.crash_free_rates_below_minimum(&report), 1);
+    /// ```
+    pub fn crash_free_rates_below_minimum(&self, report: &Report) -> usize {
+        let statistics = report.statistics.as_ref();
+        let below = |minimum: Option<f64>, rate: Option<f64>| match minimum {
+            Some(minimum) => rate.is_none_or(|rate| rate < minimum),
+            None => false,
+        };
+
+        [
+            below(
+                self.min_crash_free_users,
+                statistics.map(|statistics| statistics.crash_free_users),
+            ),
+            below(
+                self.min_crash_free_sessions,
+                statistics.and_then(|statistics| statistics.crash_free_sessions),
+            ),
+        ]
+        .iter()
+        .filter(|below| **below)
+        .count()
+    }
+
+    /// Counts the crashes of the report which reach the threshold. Without a threshold no crash
+    /// exceeds it.
+    ///
+    /// ```
+    /// # use recrep::utils::test_helper::TestHelper;
+    /// # use recrep::CrashReporter;
+    /// #
+    /// let reporter = CrashReporter::builder("abc", "org name", "app id")
+    ///     .threshold(10)
+    ///     .build()
+    ///     .unwrap();
+    /// let report = TestHelper::report_from_json("src/json_parsing/test_fixtures/two_crashes.json");
+    /// assert_eq!(reporter.crashes_exceeding_threshold(&report), 2);
+    /// ```
+    pub fn crashes_exceeding_threshold(&self, report: &Report) -> usize {
+        let threshold = match self.threshold {
+            Some(threshold) => threshold,
+            None => return 0,
+        };
+
+        report
+            .crash_list
+            .crashes
+            .iter()
+            .filter(|crash| !self.filter_out_errors || crash.is_crash())
+            .filter(|crash| crash.count.unwrap_or(0) >= threshold)
+            .count()
     }
 
     /// Reports to standard out. Optionally writes into a file at the given path
@@ -90,34 +173,125 @@ impl CrashReporter {
     /// # use recrep::model::Report;
     /// #
     /// # let crash_list = TestHelper::crash_list_from_json("src/json_parsing/test_fixtures/two_crashes.json");
-    /// let reporter = CrashReporter::with_token("abc", "org name", "app id", None, None, None,
-    /// false, false, false);
+    /// let reporter = CrashReporter::builder("abc", "org name", "app id").build().unwrap();
     /// let report = Report::new("version".to_string(), crash_list);
-    /// reporter.write_report(report, None)
+    /// reporter.write_report(report, None).unwrap()
     /// ```
-    pub fn write_report(&self, report: Report, path: Option<&str>) {
-        let formatted_report = self.format_report(report);
+    pub fn write_report(&self, report: Report, path: Option<&str>) -> Result<()> {
+        let formatted_report = self.render_report(report, self.format)?;
+        self.write(formatted_report, path)
+    }
+
+    /// Writes the builds of the version, or of the latest version if none is set.
+    pub fn list_builds(&self, outfile: Option<&str>) -> Result<()> {
+        let builds = self.crash_manager().builds(
+            self.api.as_ref(),
+            self.organization.as_str(),
+            self.application.as_str(),
+            self.version.clone(),
+            self.distribution_group.clone(),
+        )?;
+        let formatted_builds = self.format_builds(builds)?;
+        self.write(formatted_builds, outfile)
+    }
+
+    /// Fetches the crashes of the latest versions and writes how each error group developed
+    /// across them.
+    pub fn create_trend_report(&self, versions: usize, outfile: Option<&str>) -> Result<()> {
+        if !matches!(self.format, Format::Text | Format::Json) {
+            return Err(Error::InvalidOptions(
+                "The trend can only be written as text or json.",
+            ));
+        }
+        let trend = self.fetch_trend(versions)?;
+        let formatted_trend = self.format_trend(trend, self.format)?;
+        self.write(formatted_trend, outfile)
+    }
+
+    /// Prints the formatted report to standard out, or writes it into a file at the given path.
+    fn write(&self, formatted_report: String, path: Option<&str>) -> Result<()> {
         match path {
             Some(file_path) => self
                 .file_writer
-                .write(formatted_report, file_path.to_string()),
+                .write(formatted_report, file_path.to_string())?,
             None => self.printer.print(formatted_report),
         }
+        Ok(())
     }
 
-    /// Formats a crash report using the default template
+    /// Formats a crash report using the text template
     ///
     /// ```
     /// # use recrep::utils::test_helper::TestHelper;
     /// # use recrep::CrashReporter;
     /// #
-    /// let reporter = CrashReporter::with_token("abc", "org name", "app id", None, None, None,
-    /// false, false, false);
+    /// let reporter = CrashReporter::builder("abc", "org name", "app id").build().unwrap();
     /// let report = TestHelper::report_from_json("src/json_parsing/test_fixtures/two_crashes.json");
-    /// let formatted_report = reporter.format_report(report);
-    /// assert_eq!(formatted_report.chars().count(), 1352)
+    /// let formatted_report = reporter.format_report(report).unwrap();
+    /// assert_eq!(formatted_report.chars().count(), 1614)
     /// ```
-    pub fn format_report(&self, report: Report) -> String {
+    pub fn format_report(&self, report: Report) -> Result<String> {
+        self.render_report(report, Format::Text)
+    }
+
+    /// Formats a crash report in the given format. Text, Markdown and HTML are rendered from the
+    /// same data, json follows the schema of `ReportDocument`, CSV and TSV hold one row per
+    /// crash of the `ReportDocument`. Custom templates replace the text, Markdown and HTML
+    /// templates. Fails if a template can't be rendered, e.g. because it uses an unknown helper.
+    ///
+    /// ```
+    /// # use recrep::utils::test_helper::TestHelper;
+    /// # use recrep::{CrashReporter, Format};
+    /// #
+    /// let reporter = CrashReporter::builder("abc", "org name", "app id").build().unwrap();
+    /// let report = TestHelper::report_from_json("src/json_parsing/test_fixtures/two_crashes.json");
+    ///
+    /// let markdown = reporter.render_report(report, Format::Markdown).unwrap();
+    /// assert!(markdown.starts_with("# Crash newsletter of vversion"));
+    ///
+    /// let report = TestHelper::report_from_json("src/json_parsing/test_fixtures/two_crashes.json");
+    /// let html = reporter.render_report(report, Format::Html).unwrap();
+    /// assert!(html.starts_with("<!DOCTYPE html>"));
+    /// ```
+    pub fn render_report(&self, report: Report, format: Format) -> Result<String> {
+        match format {
+            Format::Json => return Ok(self.format_report_json(report)),
+            Format::Csv => {
+                return Ok(csv_column::render(
+                    &self.report_document(report).crashes,
+                    &self.columns,
+                    ',',
+                ))
+            }
+            Format::Tsv => {
+                return Ok(csv_column::render(
+                    &self.report_document(report).crashes,
+                    &self.columns,
+                    '\t',
+                ))
+            }
+            _ => {}
+        }
+
+        let data = self.report_data(report);
+        let no_crashes = data["errorGroups"].as_array().unwrap().is_empty();
+        let template_name = match format {
+            _ if no_crashes && self.empty_template.is_some() => "empty_template",
+            _ if self.template.is_some() => "template",
+            Format::Markdown => "markdown_template",
+            Format::Html => "html_template",
+            _ if no_crashes => "no_crashes_found_template",
+            _ => "crashes_template",
+        };
+        let templates = match format {
+            Format::Html => &self.html_templates,
+            _ => &self.templates,
+        };
+        Ok(templates.render(template_name, &data)?)
+    }
+
+    /// The data all report templates are rendered with.
+    fn report_data(&self, report: Report) -> serde_json::Value {
         let mut crash_list_json: serde_json::Value = json!(report.crash_list);
         let data = crash_list_json.as_object_mut().unwrap();
 
@@ -134,24 +308,21 @@ impl CrashReporter {
             json!(self.application.to_string()),
         );
         data.insert("version".to_string(), json!(report.version));
+        if !report.versions.is_empty() {
+            data.insert("versions".to_string(), json!(report.versions));
+        }
+        if let Some(time_window) = self.time_window {
+            data.insert("window".to_string(), json!(time_window));
+        }
+        if let Some(statistics) = &report.statistics {
+            data.insert("statistics".to_string(), self.statistics_data(statistics));
+        }
 
-        if data
-            .get("errorGroups")
-            .unwrap()
-            .as_array()
-            .unwrap()
-            .is_empty()
-        {
-            let mut template = Handlebars::new();
-            template
-                .register_template_string(
-                    "no_crashes_found_template",
-                    self.no_crashes_found_template(),
-                )
-                .expect("Failed to register the no crashes found template.");
-            return template
-                .render("no_crashes_found_template", &json!(data))
-                .unwrap();
+        if let Some(mut diff) = report.diff {
+            if self.filter_out_errors {
+                diff.retain(|crash| crash.is_crash());
+            }
+            data.insert("diff".to_string(), json!(diff));
         }
 
         if let Some(threshold) = self.threshold {
@@ -171,22 +342,199 @@ impl CrashReporter {
             "show_oses_affected".to_string(),
             json!(self.show_os_information),
         );
+        crash_list_json
+    }
+
+    /// Formats a crash report as json, following the versioned schema of `ReportDocument`.
+    ///
+    /// ```
+    /// # use recrep::utils::test_helper::TestHelper;
+    /// # use recrep::CrashReporter;
+    /// #
+    /// let reporter = CrashReporter::builder("abc", "org name", "app id")
+    ///     .threshold(12)
+    ///     .build()
+    ///     .unwrap();
+    /// let report = TestHelper::report_from_json("src/json_parsing/test_fixtures/two_crashes.json");
+    ///
+    /// let json: serde_json::Value = serde_json::from_str(&reporter.format_report_json(report)).unwrap();
+    /// assert_eq!(json["schema_version"], 1);
+    /// assert_eq!(json["crashes"][0]["threshold_exceeded"], true);
+    /// assert_eq!(json["crashes"][1]["threshold_exceeded"], false);
+    /// ```
+    pub fn format_report_json(&self, report: Report) -> String {
+        serde_json::to_string_pretty(&self.report_document(report))
+            .expect("Failed to serialize the report.")
+    }
+
+    /// Applies the filters and thresholds to the report, like the text format does.
+    pub fn report_document(&self, report: Report) -> ReportDocument {
+        let crash_free_rates_below_minimum = self.crash_free_rates_below_minimum(&report);
+
+        let (crashes, arithmetic_mean) = self.shown_crashes(report.crash_list.crashes);
+        let crashes = crashes
+            .into_iter()
+            .map(|crash| {
+                let count = crash.count.unwrap_or(0);
+                ReportedCrash {
+                    threshold_percentage: self
+                        .threshold
+                        .map(|threshold| count as f64 / threshold as f64 * 100.0),
+                    threshold_exceeded: self.threshold.is_some_and(|threshold| count >= threshold),
+                    ..ReportedCrash::from(&crash)
+                }
+            })
+            .collect();
+
+        let mut diff = report.diff;
+        if let Some(diff) = diff.as_mut() {
+            if self.filter_out_errors {
+                diff.retain(|crash| crash.is_crash());
+            }
+        }
+
+        ReportDocument {
+            schema_version: REPORT_SCHEMA_VERSION,
+            organization: self.organization.clone(),
+            application: self.application.clone(),
+            version: report.version,
+            versions: report.versions,
+            build: report.build,
+            time_window: self.time_window,
+            filters: ReportFilters {
+                omit_errors: self.filter_out_errors,
+                states: self.states.clone(),
+                error_group_limit: self.error_group_limit,
+                arithmetic_mean: self.use_arithmetic_mean,
+            },
+            thresholds: ReportThresholds {
+                threshold: self.threshold,
+                arithmetic_mean,
+                min_crash_free_users: self.min_crash_free_users,
+                min_crash_free_sessions: self.min_crash_free_sessions,
+                crash_free_rates_below_minimum,
+            },
+            statistics: report.statistics,
+            crashes,
+            diff: diff.as_ref().map(ReportedDiff::from),
+        }
+    }
+
+    /// Rounds the crash-free rates for display and marks the ones below their minimum.
+    fn statistics_data(&self, statistics: &CrashFreeStatistics) -> serde_json::Value {
+        let round = |rate: f64| (rate * 100.0).round() / 100.0;
+        let below = |minimum: Option<f64>, rate: f64| minimum.is_some_and(|minimum| rate < minimum);
+
+        json!({
+            "crash_free_users": round(statistics.crash_free_users),
+            "crash_free_users_below_minimum": below(self.min_crash_free_users, statistics.crash_free_users),
+            "min_crash_free_users": self.min_crash_free_users,
+            "crash_free_sessions": statistics.crash_free_sessions.map(round),
+            "crash_free_sessions_below_minimum": statistics
+                .crash_free_sessions
+                .is_some_and(|rate| below(self.min_crash_free_sessions, rate)),
+            "min_crash_free_sessions": self.min_crash_free_sessions,
+            "sessions": statistics.sessions,
+            "crashes": statistics.crashes,
+        })
+    }
+
+    /// Formats the trend of several versions as text or json. Other formats are invalid options.
+    ///
+    /// ```
+    /// # use recrep::utils::test_helper::TestHelper;
+    /// # use recrep::model::{Report, Trend};
+    /// # use recrep::{CrashReporter, Format};
+    /// #
+    /// let reporter = CrashReporter::builder("abc", "org name", "app id").build().unwrap();
+    /// let crash_list = TestHelper::crash_list_from_json("src/json_parsing/test_fixtures/two_crashes.json");
+    /// let trend = Trend::from_reports(&[
+    ///     Report::new("1.0".to_string(), crash_list.clone()),
+    ///     Report::new("1.1".to_string(), crash_list),
+    /// ]);
+    ///
+    /// let json: serde_json::Value = serde_json::from_str(&reporter.format_trend(trend, Format::Json).unwrap()).unwrap();
+    /// assert_eq!(json["versions"], serde_json::json!(["1.0", "1.1"]));
+    /// ```
+    pub fn format_trend(&self, mut trend: Trend, format: Format) -> Result<String> {
+        if self.filter_out_errors {
+            trend.retain(|crash| crash.is_crash());
+        }
+
+        match format {
+            Format::Json => {
+                Ok(serde_json::to_string_pretty(&trend).expect("Failed to serialize the trend."))
+            }
+            Format::Markdown | Format::Html | Format::Csv | Format::Tsv => Err(
+                Error::InvalidOptions("The trend can only be written as text or json."),
+            ),
+            Format::Text => {
+                let mut data = json!(trend);
+                data["organization"] = json!(self.organization);
+                data["application"] = json!(self.application);
+                Ok(self.templates.render("trend_template", &data)?)
+            }
+        }
+    }
+
+    fn format_builds(&self, builds: Vec<Version>) -> Result<String> {
+        let data = json!({
+            "organization": self.organization,
+            "application": self.application,
+            "version": builds.first().map(|build| build.short_version.clone()),
+            "builds": builds,
+        });
+        Ok(self.templates.render("builds_template", &data)?)
+    }
+
+    /// Registers the helpers, the report templates and the partials they share, followed by the
+    /// custom templates and partials, escaping values with the given function. Fails if a custom
+    /// template or partial doesn't compile.
+    fn compile_templates(&self, escape_fn: fn(&str) -> String) -> Result<Handlebars> {
         let mut template = Handlebars::new();
-        template
-            .register_template_string("crashes_template", self.crashes_template())
-            .expect("Failed to register the crashes template.");
-        template.render("crashes_template", &json!(data)).unwrap()
+        template.register_escape_fn(escape_fn);
+        template_helpers::register(
+            &mut template,
+            &self.organization,
+            &self.application,
+            Utc::now,
+        );
+        template.register_template_string("crashes_template", self.crashes_template())?;
+        template.register_template_string(
+            "no_crashes_found_template",
+            self.no_crashes_found_template(),
+        )?;
+        template.register_template_string("markdown_template", self.markdown_template())?;
+        template.register_template_string("html_template", self.html_template())?;
+        template.register_partial("diff_section", self.diff_section_template())?;
+        template.register_template_string("trend_template", self.trend_template())?;
+        template.register_template_string("builds_template", self.builds_template())?;
+
+        for (name, partial) in &self.partials {
+            template.register_partial(name, partial)?;
+        }
+        if let Some(custom_template) = &self.template {
+            template.register_template_string("template", custom_template)?;
+        }
+        if let Some(empty_template) = &self.empty_template {
+            template.register_template_string("empty_template", empty_template)?;
+        }
+        Ok(template)
     }
 
     fn crashes_template<'a>(&self) -> &'a str {
         r#"
 Hello everyone!
 
-This is the crash newsletter of v{{version}}
+This is the crash newsletter of {{#if versions}}{{#each versions}}{{#if @index}}, {{/if}}v{{this}}{{/each}}{{else}}v{{version}}{{/if}}{{#if window}} for the crashes between {{window.start}} and {{window.end}}{{/if}}{{#if statistics}}
+
+Crash-free users:    {{statistics.crash_free_users}}%{{#if statistics.crash_free_users_below_minimum}} !! BELOW THE MINIMUM OF {{statistics.min_crash_free_users}}% !!{{/if}}
+Crash-free sessions: {{#if statistics.sessions}}{{statistics.crash_free_sessions}}%{{#if statistics.crash_free_sessions_below_minimum}} !! BELOW THE MINIMUM OF {{statistics.min_crash_free_sessions}}% !!{{/if}} ({{statistics.crashes}} crashes in {{statistics.sessions}} sessions){{else}}unknown, no sessions were tracked{{/if}}{{/if}}
 
 If your team is assigned to take a look at a certain crash but decides to not to be responsible for handling the crash, please forward it 
 to another team or the community yourself.
-Please be proactive to raise awareness of crashes among the community - especially if your team can't address a crash at hand on its own.
+Please be proactive to raise awareness of crashes among the community - especially if your team can't address a crash at hand on its own.{{#if diff}}
+{{> diff_section}}{{/if}}
 
 {{#if arithmetic_mean }}
 This Crash Report uses a threshold based on the arithmetic mean of all crashes ({{ arithmetic_mean }}). Crashes that occurred less than (<) {{ arithmetic_mean }} times are excluded.
@@ -195,16 +543,33 @@ This Crash Report uses a threshold based on the arithmetic mean of all crashes (
 {{~#if threshold_exceeded}}
 !! THRESHOLD EXCEEDED !! 
 {{/if}}
+{{~#if reopened}}
+!! REOPENED !! Closed in AppCenter, but crashed again.
+{{/if}}
 {{~#if threshold }}
 {{ percentage }} ({{ count }}/{{threshold}}) of threshold reached. (crashes/threshold)
 {{~else}}
 {{ count }} times in {{ appVersion }} ({{appBuild}})
 {{~/if}}
+{{~#if builds}}
+Builds:  {{#each builds}}{{#if @index}}, {{/if}}{{ count }} times in {{ build }}{{/each}}
+{{~/if}}
+{{~#if versions}}
+Versions: {{#each versions}}{{#if @index}}, {{/if}}{{ count }} times in {{ version }}{{/each}}
+{{~/if}}
 {{~#if operatingSystemName}}
 Affected OSes: {{operatingSystemName}} on {{ deviceCount }} overall affected devices
 {{~/if}}
 
-First appeared on {{ firstOccurrence }}
+First appeared on {{ firstOccurrence }}{{#if lastOccurrence}}, last on {{ lastOccurrence }}{{/if}}
+{{~#if state}}
+State:   {{state}}
+{{~/if}}
+{{~#if exceptionType}}
+Reason:  {{exceptionType}}{{#if exceptionMessage}}: {{{exceptionMessage}}}{{/if}}
+{{~else}}{{#if exceptionMessage}}
+Reason:  {{{exceptionMessage}}}
+{{~/if}}{{/if}}
 {{~#if exceptionFile}}
 File:    {{exceptionFile}}
 {{~ /if ~}}
@@ -212,7 +577,18 @@ File:    {{exceptionFile}}
 Class:   {{exceptionClassName}}
 {{~/if ~}}
 {{~#if exceptionMethod}}
-Method:  {{exceptionMethod}}
+Method:  {{{exceptionMethod}}}
+{{~/if~}}
+{{~#if reasonFrame.codeFormatted}}
+Frame:   {{#if reasonFrame.appCode}}> {{/if}}{{{reasonFrame.codeFormatted}}}
+{{~else}}{{#unless exceptionMethod}}{{#if codeRaw}}
+Frame:   {{#if exceptionAppCode}}> {{/if}}{{{codeRaw}}}
+{{~/if}}{{/unless}}{{/if~}}
+{{~#if stacktrace}}
+Stack trace, app code marked with >:
+{{~#each stacktrace}}
+{{#if appCode}}>{{else}} {{/if}} {{#if codeFormatted}}{{{codeFormatted}}}{{else}}{{{className}}} {{{method}}}{{/if}}
+{{~/each}}
 {{~/if~}}
 {{~#if errorGroupId}}
 More on AppCenter: https://appcenter.ms/orgs/{{../organization}}/apps/{{../application}}/crashes/errors/{{errorGroupId}}/overview.
@@ -222,7 +598,7 @@ More on AppCenter: https://appcenter.ms/orgs/{{../organization}}/apps/{{../appli
 {{/each}}
 
 
-Haven't found your section above? Please checkout the rest of the crashes in the diagnostics overview: https://appcenter.ms/orgs/{{organization}}/apps/{{application}}/crashes/errors?version={{version}}&period=last30Days&status=&errorType=unhandlederror&sortCol=count&sortDir=desc.
+Haven't found your section above? Please checkout the rest of the crashes in the diagnostics overview: https://appcenter.ms/orgs/{{organization}}/apps/{{application}}/crashes/errors?version={{version}}&period={{#if window}}custom&start={{window.start}}&end={{window.end}}{{else}}last30Days{{/if}}&status=&errorType=unhandlederror&sortCol=count&sortDir=desc.
 
 Cheers,
 The Mobile Releases Team
@@ -235,14 +611,186 @@ This report was created using `recrep` for {{organization}}/{{application}}/{{ve
         r#"
 Hello everyone!
 
-This is the crash newsletter of v{{version}}
+This is the crash newsletter of {{#if versions}}{{#each versions}}{{#if @index}}, {{/if}}v{{this}}{{/each}}{{else}}v{{version}}{{/if}}{{#if window}} for the crashes between {{window.start}} and {{window.end}}{{/if}}{{#if statistics}}
+
+Crash-free users:    {{statistics.crash_free_users}}%{{#if statistics.crash_free_users_below_minimum}} !! BELOW THE MINIMUM OF {{statistics.min_crash_free_users}}% !!{{/if}}
+Crash-free sessions: {{#if statistics.sessions}}{{statistics.crash_free_sessions}}%{{#if statistics.crash_free_sessions_below_minimum}} !! BELOW THE MINIMUM OF {{statistics.min_crash_free_sessions}}% !!{{/if}} ({{statistics.crashes}} crashes in {{statistics.sessions}} sessions){{else}}unknown, no sessions were tracked{{/if}}{{/if}}
+
+Luckily this version does not have any crashes AppCenter knows about. Congratulations 🎉!{{#if diff}}
+{{> diff_section}}{{/if}}
+
+This report was created using `recrep` for {{organization}}/{{application}}/{{version}}.
+"#
+    }
+
+    fn markdown_template<'a>(&self) -> &'a str {
+        r#"# Crash newsletter of {{#if versions}}{{#each versions}}{{#if @index}}, {{/if}}v{{this}}{{/each}}{{else}}v{{version}}{{/if}}
+{{#if window}}
+Crashes between {{window.start}} and {{window.end}}.
+{{/if}}{{#if statistics}}
+| Crash-free users | Crash-free sessions |
+|---|---|
+| {{#if statistics.crash_free_users_below_minimum}}**{{statistics.crash_free_users}}%** ⚠️ below {{statistics.min_crash_free_users}}%{{else}}{{statistics.crash_free_users}}%{{/if}} | {{#if statistics.sessions}}{{#if statistics.crash_free_sessions_below_minimum}}**{{statistics.crash_free_sessions}}%** ⚠️ below {{statistics.min_crash_free_sessions}}%{{else}}{{statistics.crash_free_sessions}}%{{/if}} ({{statistics.crashes}} crashes in {{statistics.sessions}} sessions){{else}}unknown{{/if}} |
+{{/if}}{{#if arithmetic_mean}}
+Only crashes occurring at least as often as the arithmetic mean of all crashes ({{arithmetic_mean}}) are listed.
+{{/if}}
+{{#if errorGroups}}| | Crashes | Devices | Crash | Error group |
+|---|---:|---:|---|---|
+{{#each errorGroups}}| {{#if threshold_exceeded}}🔥 **Threshold exceeded**{{/if}}{{#if reopened}} ♻️ Reopened{{/if}} | {{#if threshold_exceeded}}**{{count}}**{{else}}{{count}}{{/if}}{{#if threshold}} ({{percentage}}){{/if}} | {{deviceCount}} | `{{#if exceptionClassName}}{{{(table_cell exceptionClassName)}}} {{/if}}{{{(table_cell exceptionMethod)}}}` | [{{errorGroupId}}](https://appcenter.ms/orgs/{{../organization}}/apps/{{../application}}/crashes/errors/{{errorGroupId}}/overview) |
+{{/each}}
+{{#each errorGroups~}}
+<details>
+<summary>{{count}} times: {{#if exceptionType}}{{exceptionType}}{{else}}{{exceptionClassName}} {{exceptionMethod}}{{/if}} ({{errorGroupId}})</summary>
+
+- First appeared on {{firstOccurrence}}{{#if lastOccurrence}}, last on {{lastOccurrence}}{{/if}}
+{{#if state}}- State: {{state}}
+{{/if}}{{#if exceptionMessage}}- Reason: {{exceptionMessage}}
+{{/if}}{{#if exceptionFile}}- File: `{{{exceptionFile}}}`
+{{/if}}{{#if exceptionClassName}}- Class: `{{{exceptionClassName}}}`
+{{/if}}{{#if exceptionMethod}}- Method: `{{{exceptionMethod}}}`
+{{/if}}{{#if builds}}- Builds: {{#each builds}}{{#if @index}}, {{/if}}{{count}} times in {{build}}{{/each}}
+{{/if}}{{#if versions}}- Versions: {{#each versions}}{{#if @index}}, {{/if}}{{count}} times in {{version}}{{/each}}
+{{/if}}{{#if operatingSystemName}}- Affected OSes: {{operatingSystemName}} on {{deviceCount}} overall affected devices
+{{/if}}{{#if stacktrace}}
+```
+{{#each stacktrace}}{{#if appCode}}>{{else}} {{/if}} {{#if codeFormatted}}{{{codeFormatted}}}{{else}}{{{className}}} {{{method}}}{{/if}}
+{{/each}}```
+{{/if}}
+</details>
+
+{{/each}}{{else}}Luckily this version does not have any crashes AppCenter knows about. Congratulations 🎉!
 
-Luckily this version does not have any crashes AppCenter knows about. Congratulations 🎉!
+{{/if}}{{#if diff}}<details>
+<summary>Compared to v{{diff.base_version}}</summary>
+
+Crashes changing by more than {{diff.regression_threshold}}% count as regressed or improved.
+
+| Change | Crashes | Crash | Error group |
+|---|---|---|---|
+{{#each diff.new}}| New | {{count}} | `{{#if crash.exceptionClassName}}{{{(table_cell crash.exceptionClassName)}}} {{/if}}{{{(table_cell crash.exceptionMethod)}}}` | {{crash.errorGroupId}} |
+{{/each}}{{#each diff.regressed}}| Regressed | {{base_count}} → {{count}} | `{{#if crash.exceptionClassName}}{{{(table_cell crash.exceptionClassName)}}} {{/if}}{{{(table_cell crash.exceptionMethod)}}}` | {{crash.errorGroupId}} |
+{{/each}}{{#each diff.improved}}| Improved | {{base_count}} → {{count}} | `{{#if crash.exceptionClassName}}{{{(table_cell crash.exceptionClassName)}}} {{/if}}{{{(table_cell crash.exceptionMethod)}}}` | {{crash.errorGroupId}} |
+{{/each}}{{#each diff.resolved}}| Resolved | {{base_count}} → 0 | `{{#if crash.exceptionClassName}}{{{(table_cell crash.exceptionClassName)}}} {{/if}}{{{(table_cell crash.exceptionMethod)}}}` | {{crash.errorGroupId}} |
+{{/each}}
+</details>
+
+{{/if}}[All crashes of v{{version}} on AppCenter](https://appcenter.ms/orgs/{{organization}}/apps/{{application}}/crashes/errors?version={{version}}&period={{#if window}}custom&start={{window.start}}&end={{window.end}}{{else}}last30Days{{/if}}&status=&errorType=unhandlederror&sortCol=count&sortDir=desc)
 
 This report was created using `recrep` for {{organization}}/{{application}}/{{version}}.
 "#
     }
 
+    fn html_template<'a>(&self) -> &'a str {
+        r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>Crash newsletter of {{organization}}/{{application}} v{{version}}</title>
+<style>
+body { font-family: -apple-system, "Segoe UI", Helvetica, Arial, sans-serif; color: #24292e; max-width: 60em; margin: 2em auto; }
+table { border-collapse: collapse; margin: 1em 0; }
+th, td { border: 1px solid #d1d5da; padding: 0.3em 0.6em; text-align: left; vertical-align: top; }
+td.number { text-align: right; }
+tr.exceeded { background: #ffeef0; }
+.exceeded-marker { color: #cb2431; font-weight: bold; }
+.reopened-marker { color: #b08800; font-weight: bold; }
+.below-minimum { color: #cb2431; font-weight: bold; }
+code, pre { font-family: SFMono-Regular, Consolas, Menlo, monospace; font-size: 0.9em; }
+pre { background: #f6f8fa; padding: 0.6em; overflow-x: auto; }
+.app-code { font-weight: bold; }
+</style>
+</head>
+<body>
+<h1>Crash newsletter of {{#if versions}}{{#each versions}}{{#if @index}}, {{/if}}v{{this}}{{/each}}{{else}}v{{version}}{{/if}}</h1>
+{{#if window}}<p>Crashes between {{window.start}} and {{window.end}}.</p>
+{{/if}}{{#if statistics}}<table>
+<tr><th>Crash-free users</th><th>Crash-free sessions</th></tr>
+<tr><td{{#if statistics.crash_free_users_below_minimum}} class="below-minimum"{{/if}}>{{statistics.crash_free_users}}%{{#if statistics.crash_free_users_below_minimum}}, below {{statistics.min_crash_free_users}}%{{/if}}</td><td{{#if statistics.crash_free_sessions_below_minimum}} class="below-minimum"{{/if}}>{{#if statistics.sessions}}{{statistics.crash_free_sessions}}%{{#if statistics.crash_free_sessions_below_minimum}}, below {{statistics.min_crash_free_sessions}}%{{/if}} ({{statistics.crashes}} crashes in {{statistics.sessions}} sessions){{else}}unknown{{/if}}</td></tr>
+</table>
+{{/if}}{{#if arithmetic_mean}}<p>Only crashes occurring at least as often as the arithmetic mean of all crashes ({{arithmetic_mean}}) are listed.</p>
+{{/if}}{{#if errorGroups}}<table>
+<tr><th></th><th>Crashes</th><th>Devices</th><th>Crash</th><th>Error group</th></tr>
+{{#each errorGroups}}<tr{{#if threshold_exceeded}} class="exceeded"{{/if}}><td>{{#if threshold_exceeded}}<span class="exceeded-marker">Threshold exceeded</span> {{/if}}{{#if reopened}}<span class="reopened-marker">Reopened</span>{{/if}}</td><td class="number">{{count}}{{#if threshold}} ({{percentage}}){{/if}}</td><td class="number">{{deviceCount}}</td><td><code>{{#if exceptionClassName}}{{exceptionClassName}} {{/if}}{{exceptionMethod}}</code></td><td><a href="https://appcenter.ms/orgs/{{../organization}}/apps/{{../application}}/crashes/errors/{{errorGroupId}}/overview">{{errorGroupId}}</a></td></tr>
+{{/each}}</table>
+{{#each errorGroups}}<details>
+<summary>{{count}} times: {{#if exceptionType}}{{exceptionType}}{{else}}{{exceptionClassName}} {{exceptionMethod}}{{/if}} ({{errorGroupId}})</summary>
+<ul>
+<li>First appeared on {{firstOccurrence}}{{#if lastOccurrence}}, last on {{lastOccurrence}}{{/if}}</li>
+{{#if state}}<li>State: {{state}}</li>
+{{/if}}{{#if exceptionMessage}}<li>Reason: {{exceptionMessage}}</li>
+{{/if}}{{#if exceptionFile}}<li>File: <code>{{exceptionFile}}</code></li>
+{{/if}}{{#if exceptionClassName}}<li>Class: <code>{{exceptionClassName}}</code></li>
+{{/if}}{{#if exceptionMethod}}<li>Method: <code>{{exceptionMethod}}</code></li>
+{{/if}}{{#if builds}}<li>Builds: {{#each builds}}{{#if @index}}, {{/if}}{{count}} times in {{build}}{{/each}}</li>
+{{/if}}{{#if versions}}<li>Versions: {{#each versions}}{{#if @index}}, {{/if}}{{count}} times in {{version}}{{/each}}</li>
+{{/if}}{{#if operatingSystemName}}<li>Affected OSes: {{operatingSystemName}} on {{deviceCount}} overall affected devices</li>
+{{/if}}</ul>
+{{#if stacktrace}}<pre>{{#each stacktrace}}{{#if appCode}}<span class="app-code">&gt; {{else}}  {{/if}}{{#if codeFormatted}}{{codeFormatted}}{{else}}{{className}} {{method}}{{/if}}{{#if appCode}}</span>{{/if}}
+{{/each}}</pre>
+{{/if}}</details>
+{{/each}}{{else}}<p>Luckily this version does not have any crashes AppCenter knows about. Congratulations 🎉!</p>
+{{/if}}{{#if diff}}<details>
+<summary>Compared to v{{diff.base_version}}</summary>
+<p>Crashes changing by more than {{diff.regression_threshold}}% count as regressed or improved.</p>
+<table>
+<tr><th>Change</th><th>Crashes</th><th>Crash</th><th>Error group</th></tr>
+{{#each diff.new}}<tr><td>New</td><td class="number">{{count}}</td><td><code>{{#if crash.exceptionClassName}}{{crash.exceptionClassName}} {{/if}}{{crash.exceptionMethod}}</code></td><td>{{crash.errorGroupId}}</td></tr>
+{{/each}}{{#each diff.regressed}}<tr><td>Regressed</td><td class="number">{{base_count}} → {{count}}</td><td><code>{{#if crash.exceptionClassName}}{{crash.exceptionClassName}} {{/if}}{{crash.exceptionMethod}}</code></td><td>{{crash.errorGroupId}}</td></tr>
+{{/each}}{{#each diff.improved}}<tr><td>Improved</td><td class="number">{{base_count}} → {{count}}</td><td><code>{{#if crash.exceptionClassName}}{{crash.exceptionClassName}} {{/if}}{{crash.exceptionMethod}}</code></td><td>{{crash.errorGroupId}}</td></tr>
+{{/each}}{{#each diff.resolved}}<tr><td>Resolved</td><td class="number">{{base_count}} → 0</td><td><code>{{#if crash.exceptionClassName}}{{crash.exceptionClassName}} {{/if}}{{crash.exceptionMethod}}</code></td><td>{{crash.errorGroupId}}</td></tr>
+{{/each}}</table>
+</details>
+{{/if}}<p><a href="https://appcenter.ms/orgs/{{organization}}/apps/{{application}}/crashes/errors?version={{version}}&amp;period={{#if window}}custom&amp;start={{window.start}}&amp;end={{window.end}}{{else}}last30Days{{/if}}&amp;status=&amp;errorType=unhandlederror&amp;sortCol=count&amp;sortDir=desc">All crashes of v{{version}} on AppCenter</a></p>
+<p>This report was created using <code>recrep</code> for {{organization}}/{{application}}/{{version}}.</p>
+</body>
+</html>
+"#
+    }
+
+    fn diff_section_template<'a>(&self) -> &'a str {
+        r#"
+Compared to v{{diff.base_version}}, crashes changing by more than {{diff.regression_threshold}}% count as regressed or improved:
+{{~#each diff.new}}
+NEW        {{ count }} times: {{crash.exceptionClassName}} {{crash.exceptionMethod}} ({{crash.errorGroupId}})
+{{~/each}}
+{{~#each diff.regressed}}
+REGRESSED  {{ base_count }} → {{ count }} times: {{crash.exceptionClassName}} {{crash.exceptionMethod}} ({{crash.errorGroupId}})
+{{~/each}}
+{{~#each diff.improved}}
+IMPROVED   {{ base_count }} → {{ count }} times: {{crash.exceptionClassName}} {{crash.exceptionMethod}} ({{crash.errorGroupId}})
+{{~/each}}
+{{~#each diff.resolved}}
+RESOLVED   {{ base_count }} times in v{{../diff.base_version}}: {{crash.exceptionClassName}} {{crash.exceptionMethod}} ({{crash.errorGroupId}})
+{{~/each}}
+"#
+    }
+
+    fn trend_template<'a>(&self) -> &'a str {
+        r#"
+Hello everyone!
+
+This is the crash trend of {{#each versions}}{{#if @index}}, {{/if}}v{{this}}{{/each}}, from the oldest to the most recent version.
+{{#each error_groups}}
+{{sparkline}}  {{crash.exceptionClassName}} {{crash.exceptionMethod}} ({{error_group_id}})
+Crashes: {{#each points}}{{#if @index}} → {{/if}}{{count}}{{/each}}
+Devices: {{#each points}}{{#if @index}} → {{/if}}{{deviceCount}}{{/each}}
+More on AppCenter: https://appcenter.ms/orgs/{{../organization}}/apps/{{../application}}/crashes/errors/{{error_group_id}}/overview.
+{{else}}
+Luckily none of these versions has crashes AppCenter knows about. Congratulations 🎉!
+{{/each}}
+
+This report was created using `recrep` for {{organization}}/{{application}}.
+"#
+    }
+
+    fn builds_template<'a>(&self) -> &'a str {
+        r#"
+Builds of {{organization}}/{{application}} v{{version}}, most recent first:{{#each builds}}
+{{version}}  uploaded at {{uploaded_at}}{{#if distribution_groups}} to {{#each distribution_groups}}{{#if @index}}, {{/if}}{{name}}{{/each}}{{/if}}
+{{~/each}}
+"#
+    }
+
     fn add_threshold_values_to_individual_crashes(
         &self,
         crash_data: &mut serde_json::Map<String, serde_json::Value>,
@@ -271,12 +819,29 @@ This report was created using `recrep` for {{organization}}/{{application}}/{{ve
         }
     }
 
+    /// The crashes the report shows: without errors if they are omitted, and without the crashes
+    /// below the arithmetic mean if it is used. Also returns the arithmetic mean, if used.
+    fn shown_crashes(&self, mut crashes: Vec<Crash>) -> (Vec<Crash>, Option<u64>) {
+        if self.filter_out_errors {
+            crashes.retain(|crash| crash.is_crash());
+        }
+        let arithmetic_mean = if self.use_arithmetic_mean {
+            arithmetic_mean(&crashes)
+        } else {
+            None
+        };
+        if let Some(arithmetic_mean) = arithmetic_mean {
+            crashes.retain(|crash| crash.count.unwrap_or(0) >= arithmetic_mean);
+        }
+        (crashes, arithmetic_mean)
+    }
+
     fn filter_out_errors(&self, crash_data: &mut serde_json::Map<String, serde_json::Value>) {
         let value = &mut crash_data["errorGroups"];
         let all_crashes: &mut Vec<serde_json::Value> = value.as_array_mut().unwrap();
 
         // errorGroupIds ending with `u` indicate errors, a missing `u` indicates an error
-        all_crashes.retain(|crash| crash["errorGroupId"].as_str().unwrap().ends_with("u") );
+        all_crashes.retain(|crash| crash["errorGroupId"].as_str().unwrap().ends_with("u"));
     }
 
     fn add_operating_system_information(
@@ -289,35 +854,36 @@ This report was created using `recrep` for {{organization}}/{{application}}/{{ve
         // object: Object<Map<String, Value>>
         for object in all_crashes.iter_mut() {
             let crash = object.as_object_mut().unwrap();
-            let oses = crash["operating_systems"].as_array().unwrap();
-            let crash_amount_devices_overall = crash["deviceCount"].as_f64().unwrap();
-            let mut formatted = oses
+            // the details of an error group may be missing, its crashes have no known OSes then
+            let no_oses = Vec::new();
+            let oses = crash
+                .get("operating_systems")
+                .and_then(|oses| oses.as_array())
+                .unwrap_or(&no_oses);
+            let crash_amount_devices_overall = crash["deviceCount"].as_f64().unwrap_or(0.0);
+            let shown_oses: Vec<String> = oses
                 .iter()
                 .filter_map(|os| {
-                    let crash_amount_os = os["errorCount"].as_f64().unwrap();
-                    let percentage =
-                        (crash_amount_os / crash_amount_devices_overall) as f64 * 100.0;
+                    let crash_amount_os = os["errorCount"].as_f64().unwrap_or(0.0);
+                    let percentage = crash_amount_os / crash_amount_devices_overall * 100.0;
                     if percentage > 5.0 {
-                        let os_string = os["operatingSystemName"].as_str().unwrap();
+                        let os_string = os["operatingSystemName"].as_str().unwrap_or_default();
                         Some(format!(
-                            "{}: {} crashes ({:.2}%) ",
+                            "{}: {} crashes ({:.2}%)",
                             os_string, crash_amount_os, percentage
                         ))
                     } else {
                         None
                     }
                 })
-                .fold(String::new(), |mut formatted, sub| {
-                    formatted += sub.as_str();
-                    formatted += "| ";
-                    formatted
-                });
-
-            // remove trailing "| ", as the previous 'fold' section can't know when
-            // to not put the suffix.
-            formatted.truncate(formatted.len() - 3);
+                .collect();
+            if shown_oses.is_empty() {
+                continue;
+            }
+
+            let mut formatted = shown_oses.join(" | ");
             let amount_of_affected_oses_shown = formatted.matches("|").count();
-            if amount_of_affected_oses_shown < crash["deviceCount"].as_u64().unwrap() as usize {
+            if amount_of_affected_oses_shown < crash["deviceCount"].as_u64().unwrap_or(0) as usize {
                 formatted += " and more"
             }
             crash.insert("operatingSystemName".to_string(), json!(formatted));
@@ -352,93 +918,920 @@ This report was created using `recrep` for {{organization}}/{{application}}/{{ve
         crash_data.insert("arithmetic_mean".to_string(), json!(arithmetic_mean));
     }
 
-    fn crashes_from_app_center(&self) -> Result<Report, &'static str> {
-        let api = AppCenter::new(self.token.clone());
-        self.crashes_from_api(api)
+    /// Fetches the crashes from the API, including the affected operating systems if requested.
+    ///
+    /// ```
+    /// use recrep::api::MockAPI;
+    /// use recrep::CrashReporter;
+    ///
+    /// let api = MockAPI::with_two_crashes();
+    /// let reporter = CrashReporter::builder("abc", "org", "app")
+    ///     .version("1.2.3")
+    ///     .api(Box::new(api))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let report = reporter.fetch_report().unwrap();
+    /// assert_eq!(report.crash_list.crashes.len(), 2);
+    /// ```
+    pub fn fetch_report(&self) -> Result<Report> {
+        let mut crash_report = self.fetch_crashes()?;
+        if self.show_os_information {
+            let error_groups = self.download_group_details_for_crashes(&crash_report)?;
+            crash_report.assign_operating_system_details(error_groups);
+        }
+        if let Some(frames) = self.stacktrace_frames {
+            let stacktraces = self.download_stacktraces_for_crashes(&crash_report, frames);
+            crash_report.assign_stacktraces(stacktraces?);
+        }
+        if self.break_down_by_build && crash_report.build.is_none() {
+            let build_counts = self.fetch_build_counts(&crash_report)?;
+            crash_report.assign_build_counts(build_counts);
+        }
+        if crash_report.versions.is_empty() && crash_report.build.is_none() {
+            crash_report.statistics = self.fetch_crash_free_statistics(&crash_report)?;
+        }
+        if let Some(base_version) = &self.base_version {
+            let base_report = self.fetch_crashes_of_version(Some(base_version.clone()))?;
+            crash_report.diff = Some(VersionDiff::between(
+                &base_report.version,
+                &base_report.crash_list,
+                &crash_report.version,
+                &crash_report.crash_list,
+                self.regression_threshold,
+            ));
+        }
+        Ok(crash_report)
+    }
+
+    /// Fetches the crashes of the latest `versions` versions, of the distribution group if one is
+    /// set, and tracks each error group across them.
+    ///
+    /// ```
+    /// use recrep::api::MockAPI;
+    /// use recrep::utils::TestHelper;
+    /// use recrep::CrashReporter;
+    ///
+    /// let api = MockAPI::with_two_crashes().and_versions_json(TestHelper::fixture(
+    ///     "src/json_parsing/test_fixtures/versions.json",
+    /// ));
+    /// let reporter = CrashReporter::builder("abc", "org", "app")
+    ///     .api(Box::new(api))
+    ///     .build()
+    ///     .unwrap();
+    ///
+    /// let trend = reporter.fetch_trend(3).unwrap();
+    /// assert_eq!(trend.versions, vec!["7.41.2", "7.41.3", "7.42.0"]);
+    /// assert_eq!(trend.error_groups[0].points.len(), 3);
+    /// ```
+    pub fn fetch_trend(&self, versions: usize) -> Result<Trend> {
+        if versions == 0 {
+            return Err(Error::InvalidOptions(
+                "The trend needs at least one version.",
+            ));
+        }
+
+        let crash_downloader = self.crash_manager();
+        let mut latest_versions = crash_downloader.latest_versions(
+            self.api.as_ref(),
+            self.organization.as_str(),
+            self.application.as_str(),
+            versions,
+            self.distribution_group.clone(),
+        )?;
+        latest_versions.reverse();
+
+        let reports = latest_versions
+            .into_iter()
+            .map(|version| self.fetch_crashes_of_version(Some(version)))
+            .collect::<Result<Vec<Report>>>()?;
+        Ok(Trend::from_reports(&reports))
+    }
+
+    fn fetch_crashes(&self) -> Result<Report> {
+        if let Some(since) = self.since {
+            return self.crash_manager().crash_list_since(
+                self.api.as_ref(),
+                self.organization.as_str(),
+                self.application.as_str(),
+                since,
+                self.distribution_group.clone(),
+                self.error_group_limit,
+            );
+        }
+        match (&self.version, &self.build) {
+            (Some(version), Some(build)) => self.crash_manager().crash_list_for_build(
+                self.api.as_ref(),
+                self.organization.as_str(),
+                self.application.as_str(),
+                version.clone(),
+                build.clone(),
+                self.error_group_limit,
+            ),
+            _ => self.fetch_crashes_of_version(self.version.clone()),
+        }
+    }
+
+    /// Fetches the crashes of each build of the reported version, keyed by error group. Nothing is
+    /// fetched if the version has a single build only.
+    fn fetch_build_counts(
+        &self,
+        crash_report: &Report,
+    ) -> Result<HashMap<String, Vec<BuildCount>>> {
+        let crash_downloader = self.crash_manager();
+        let mut builds: Vec<String> = Vec::new();
+        for version in crash_downloader.builds(
+            self.api.as_ref(),
+            self.organization.as_str(),
+            self.application.as_str(),
+            Some(crash_report.version.clone()),
+            None,
+        )? {
+            if let Some(build) = version.build {
+                if !builds.contains(&build) {
+                    builds.push(build);
+                }
+            }
+        }
+
+        let mut build_counts: HashMap<String, Vec<BuildCount>> = HashMap::new();
+        if builds.len() < 2 {
+            return Ok(build_counts);
+        }
+        let build_reports = crash_downloader.crash_lists_for_builds(
+            self.api.as_ref(),
+            self.organization.as_str(),
+            self.application.as_str(),
+            crash_report.version.as_str(),
+            &builds,
+            self.error_group_limit,
+            self.parallel_requests,
+        );
+        for (build, build_report) in builds.into_iter().zip(build_reports) {
+            for crash in build_report?.crash_list.crashes {
+                if let Some(error_group_id) = crash.error_group_id {
+                    build_counts
+                        .entry(error_group_id)
+                        .or_default()
+                        .push(BuildCount {
+                            build: build.clone(),
+                            count: crash.count.unwrap_or(0),
+                            device_count: crash.device_count.unwrap_or(0),
+                        });
+                }
+            }
+        }
+        Ok(build_counts)
+    }
+
+    /// Fetches the crash-free rates of the reported version. Statistics which can't be fetched
+    /// are unknown, and only fail the report if a minimum rate has to be checked.
+    fn fetch_crash_free_statistics(
+        &self,
+        crash_report: &Report,
+    ) -> Result<Option<CrashFreeStatistics>> {
+        let statistics = self.crash_manager().crash_free_statistics(
+            self.api.as_ref(),
+            self.organization.as_str(),
+            self.application.as_str(),
+            crash_report.version.as_str(),
+        );
+        let requires_statistics =
+            self.min_crash_free_users.is_some() || self.min_crash_free_sessions.is_some();
+        match statistics {
+            Ok(statistics) => Ok(Some(statistics)),
+            Err(e) if !requires_statistics => {
+                eprintln!("No crash-free statistics found. Error: {}", e);
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    fn crash_manager(&self) -> crashes::CrashManager {
+        crashes::CrashManager {
+            version_ordering: self.version_ordering,
+            time_window: self.time_window,
+            states: self.states.clone(),
+        }
     }
 
-    fn crashes_from_api(&self, api: impl API) -> Result<Report, &'static str> {
-        let crash_downloader = crashes::CrashManager {};
+    fn fetch_crashes_of_version(&self, version: Option<String>) -> Result<Report> {
+        let crash_downloader = self.crash_manager();
 
         crash_downloader.crash_list(
-            &api,
+            self.api.as_ref(),
             self.organization.as_str(),
             self.application.as_str(),
-            self.version.clone(),
+            version,
             self.distribution_group.clone(),
+            self.error_group_limit,
         )
     }
 
     fn download_group_details_for_crashes(
         &self,
         crash_report: &Report,
-    ) -> HashMap<String, Vec<OperatingSystemCount>> {
-        let api = AppCenter::new(self.token.clone());
-        let crash_downloader = crashes::CrashManager {};
+    ) -> Result<HashMap<String, Vec<OperatingSystemCount>>> {
+        let crash_downloader = self.crash_manager();
+        let error_group_ids: Vec<String> = crash_report
+            .crash_list
+            .crashes
+            .iter()
+            .filter_map(|crash| crash.error_group_id.clone())
+            .collect();
+        let details = crash_downloader.error_groups_details(
+            self.api.as_ref(),
+            &error_group_ids,
+            self.application.as_str(),
+            self.organization.as_str(),
+            self.parallel_requests,
+        );
 
         let mut error_groups: HashMap<String, Vec<OperatingSystemCount>> = HashMap::new();
-        for crash in crash_report.crash_list.crashes.iter() {
-            if let Some(error_group_id) = &crash.error_group_id {
-                match crash_downloader.error_group_details(
-                    &api,
-                    error_group_id.as_str(),
-                    self.application.as_str(),
-                    self.organization.as_str(),
-                ) {
-                    Ok(error_group) => {
-                        error_groups.insert(
-                            crash.error_group_id.as_ref().unwrap().to_string(),
-                            error_group.operating_systems,
-                        );
-                    }
-                    Err(e) => {
-                        println!(
-                            "No error group found, for ID: {}. Error: {}",
-                            error_group_id, e
-                        );
-                        continue;
-                    }
+        for (error_group_id, details) in error_group_ids.into_iter().zip(details) {
+            match details {
+                Ok(error_group) => {
+                    error_groups.insert(error_group_id, error_group.operating_systems);
+                }
+                // details of a single error group may be missing, this is no reason to
+                // skip the whole report
+                Err(e @ Error::NotFound)
+                | Err(e @ Error::Json { .. })
+                | Err(e @ Error::MissingRecording(_)) => {
+                    eprintln!(
+                        "No error group found, for ID: {}. Error: {}",
+                        error_group_id, e
+                    );
+                    continue;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(error_groups)
+    }
+
+    /// Fetches the stack traces of the crashes the report shows and keeps the topmost `frames`
+    /// frames of each. Crashes without a stack trace are reported without one.
+    fn download_stacktraces_for_crashes(
+        &self,
+        crash_report: &Report,
+        frames: usize,
+    ) -> Result<HashMap<String, Vec<StackFrame>>> {
+        let (shown_crashes, _) = self.shown_crashes(crash_report.crash_list.crashes.clone());
+        let error_group_ids: Vec<String> = shown_crashes
+            .into_iter()
+            .filter_map(|crash| crash.error_group_id)
+            .collect();
+        let stacktraces = self.crash_manager().stacktraces(
+            self.api.as_ref(),
+            &error_group_ids,
+            self.application.as_str(),
+            self.organization.as_str(),
+            self.parallel_requests,
+        );
+
+        let mut top_frames: HashMap<String, Vec<StackFrame>> = HashMap::new();
+        for (error_group_id, stacktrace) in error_group_ids.into_iter().zip(stacktraces) {
+            match stacktrace {
+                Ok(stacktrace) => {
+                    top_frames.insert(error_group_id, stacktrace.top_frames(frames));
                 }
+                Err(e @ Error::NotFound)
+                | Err(e @ Error::Json { .. })
+                | Err(e @ Error::MissingRecording(_)) => {
+                    eprintln!(
+                        "No stack trace found, for ID: {}. Error: {}",
+                        error_group_id, e
+                    );
+                }
+                Err(e) => return Err(e),
             }
         }
-        return error_groups;
+        Ok(top_frames)
     }
 }
 
+/// The arithmetic mean of the crash counts, or `None` if there are no crashes.
+fn arithmetic_mean(crashes: &[Crash]) -> Option<u64> {
+    let sum_of_all_crash_occurrences: u64 =
+        crashes.iter().map(|crash| crash.count.unwrap_or(0)).sum();
+    if sum_of_all_crash_occurrences == 0 {
+        return None;
+    }
+    Some(sum_of_all_crash_occurrences / crashes.len() as u64)
+}
+
+#[test]
+fn test_report_includes_operating_systems_from_api() {
+    let api = api::MockAPI::with_two_crashes().and_operating_systems_json(
+        utils::test_helper::TestHelper::fixture(
+            "src/json_parsing/test_fixtures/operating_systems.json",
+        ),
+    );
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .version("7.41.3")
+        .show_os_information(true)
+        .api(Box::new(api))
+        .build()
+        .unwrap();
+
+    let report = reporter.fetch_report().unwrap();
+    for crash in report.crash_list.crashes {
+        assert_eq!(crash.operating_systems.unwrap().len(), 3);
+    }
+}
+
+#[test]
+fn test_report_formatting_tolerates_missing_operating_systems() {
+    // the mock serves no error group details, so every group is skipped
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .version("7.41.3")
+        .show_os_information(true)
+        .api(Box::new(api::MockAPI::with_two_crashes()))
+        .build()
+        .unwrap();
+
+    let report = reporter.fetch_report().unwrap();
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert!(!formatted_report.contains("Affected OSes"));
+}
+
+#[test]
+fn test_report_breaks_counts_down_per_build() {
+    let api = api::MockAPI::with_two_crashes().and_versions_json(
+        utils::test_helper::TestHelper::fixture("src/json_parsing/test_fixtures/versions.json"),
+    );
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .version("7.41.3")
+        .break_down_by_build(true)
+        .api(Box::new(api))
+        .build()
+        .unwrap();
+
+    let report = reporter.fetch_report().unwrap();
+    for crash in &report.crash_list.crashes {
+        let builds: Vec<&str> = crash
+            .builds
+            .iter()
+            .flatten()
+            .map(|build_count| build_count.build.as_str())
+            .collect();
+        assert_eq!(builds, vec!["10168", "10167"]);
+    }
+
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert!(formatted_report.contains("\nBuilds:  15 times in 10168, 15 times in 10167\n"));
+}
+
+#[test]
+fn test_report_combines_versions_uploaded_since() {
+    let api = api::MockAPI::with_two_crashes().and_versions_json(
+        utils::test_helper::TestHelper::fixture("src/json_parsing/test_fixtures/versions.json"),
+    );
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .since("2019-05-10T00:00:00Z".parse().unwrap())
+        .api(Box::new(api))
+        .build()
+        .unwrap();
+
+    let report = reporter.fetch_report().unwrap();
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert!(formatted_report.contains("This is the crash newsletter of v7.42.0, v7.41.3\n"));
+    assert!(formatted_report.contains("\nVersions: 15 times in 7.42.0, 15 times in 7.41.3\n"));
+}
+
+#[test]
+fn test_report_formatting_reflects_the_time_window() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .time_window(model::TimeWindow::new(
+            "2019-05-15T00:00:00Z".parse().unwrap(),
+            "2019-05-16T00:00:00Z".parse().unwrap(),
+        ))
+        .build()
+        .unwrap();
+    let report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert!(formatted_report
+        .contains("for the crashes between 2019-05-15T00:00:00Z and 2019-05-16T00:00:00Z"));
+    assert!(formatted_report
+        .contains("&period=custom&start=2019-05-15T00:00:00Z&end=2019-05-16T00:00:00Z&status="));
+}
+
+#[test]
+fn test_builds_formatting() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .version("7.41.3")
+        .build()
+        .unwrap();
+    let versions: Vec<Version> = serde_json::from_str(&utils::test_helper::TestHelper::fixture(
+        "src/json_parsing/test_fixtures/versions.json",
+    ))
+    .unwrap();
+    let builds = model::VersionList::builds_of_version(versions, "7.41.3");
+
+    let formatted_builds = reporter.format_builds(builds).unwrap();
+    assert_eq!(
+        formatted_builds,
+        "\nBuilds of org/app v7.41.3, most recent first:\n\
+         10168  uploaded at 2019-05-16T08:03:51Z to Beta testers\n\
+         10167  uploaded at 2019-05-15T12:40:15Z to A user group\n"
+    );
+}
+
+#[test]
+fn test_report_formatting_includes_crash_free_rates() {
+    let api = api::MockAPI::with_two_crashes().and_statistics();
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .version("7.41.3")
+        .min_crash_free_users(99.5)
+        .api(Box::new(api))
+        .build()
+        .unwrap();
+
+    let report = reporter.fetch_report().unwrap();
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert!(
+        formatted_report.contains("Crash-free users:    99.12% !! BELOW THE MINIMUM OF 99.5% !!\n")
+    );
+    assert!(
+        formatted_report.contains("Crash-free sessions: 99.8% (50 crashes in 25000 sessions)\n")
+    );
+}
+
+#[test]
+fn test_missing_crash_free_rates_only_fail_reports_with_minimums() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .version("7.41.3")
+        .api(Box::new(api::MockAPI::with_two_crashes()))
+        .build()
+        .unwrap();
+    assert!(reporter.fetch_report().unwrap().statistics.is_none());
+
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .version("7.41.3")
+        .min_crash_free_sessions(99.9)
+        .api(Box::new(api::MockAPI::with_two_crashes()))
+        .build()
+        .unwrap();
+    assert!(matches!(reporter.fetch_report(), Err(Error::NotFound)));
+}
+
+#[test]
+fn test_crash_free_rates_are_not_fetched_for_builds() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .version("7.41.3")
+        .app_build("10167")
+        .api(Box::new(api::MockAPI::with_two_crashes().and_statistics()))
+        .build()
+        .unwrap();
+    assert!(reporter.fetch_report().unwrap().statistics.is_none());
+
+    let result = CrashReporter::builder("abc", "org", "app")
+        .version("7.41.3")
+        .app_build("10167")
+        .min_crash_free_users(99.5)
+        .build();
+    assert!(matches!(result, Err(Error::InvalidOptions(_))));
+}
+
+#[test]
+fn test_report_formatting_says_what_went_wrong() {
+    let reporter = CrashReporter::builder("abc", "org", "app").build().unwrap();
+    let mut report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+    report.crash_list.crashes[1].reason_frame = Some(model::StackFrame {
+        code_formatted: Some(
+            "-[BITPLCrashReportMachExceptionInfo codes] (BITPLCrashReport.m:12)".to_string(),
+        ),
+        ..model::StackFrame::default()
+    });
+
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert!(formatted_report.contains(
+        "First appeared on 2019-05-16T20:26:39Z, last on 2019-05-19T22:22:29Z
+State:   Open
+Reason:  SIGSEGV: Selector name found in current argument registers: delegate
+File:    FeedViewController.swift
+Method:  XNGFeed.FeedViewController.(showComboxEntryView in _494CFAE549D032F287E30AAB63618902)() -> ()
+More on AppCenter:"
+    ));
+    assert!(formatted_report.contains(
+        "Reason:  NSInvalidArgumentException: *** -[__NSArrayM insertObject:atIndex:]: object cannot be nil
+Class:   BITPLCrashReportMachExceptionInfo
+Method:  codes
+Frame:   -[BITPLCrashReportMachExceptionInfo codes] (BITPLCrashReport.m:12)
+More on AppCenter:"
+    ));
+}
+
+#[test]
+fn test_json_report_applies_filters_like_the_text_report() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .use_arithmetic_mean(true)
+        .filter_out_errors(true)
+        .format(Format::Json)
+        .build()
+        .unwrap();
+    let report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&reporter.format_report_json(report)).unwrap();
+    assert_eq!(json["schema_version"], REPORT_SCHEMA_VERSION);
+    assert_eq!(json["filters"]["omit_errors"], true);
+    assert_eq!(json["thresholds"]["arithmetic_mean"], 12);
+    assert_eq!(json["crashes"].as_array().unwrap().len(), 1);
+    assert_eq!(json["crashes"][0]["error_group_id"], "92620314u");
+    assert_eq!(
+        json["crashes"][0]["threshold_percentage"],
+        serde_json::Value::Null
+    );
+}
+
+#[test]
+fn test_json_report_uses_snake_case_fields() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .format(Format::Json)
+        .build()
+        .unwrap();
+    let report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_str(&reporter.format_report_json(report)).unwrap();
+    let crash = json["crashes"][1].as_object().unwrap();
+    assert_eq!(crash["device_count"], 4);
+    assert_eq!(
+        crash["exception_class_name"],
+        "BITPLCrashReportMachExceptionInfo"
+    );
+    assert_eq!(crash["state"], "Open");
+    assert!(crash.keys().all(|key| !key.chars().any(char::is_uppercase)));
+}
+
+#[test]
+fn test_markdown_report_highlights_crashes_exceeding_the_threshold() {
+    let api = api::MockAPI::with_two_crashes()
+        .and_statistics()
+        .and_stacktrace();
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .version("7.41.3")
+        .threshold(12)
+        .stacktrace_frames(3)
+        .api(Box::new(api))
+        .build()
+        .unwrap();
+
+    let report = reporter.fetch_report().unwrap();
+    let markdown = reporter.render_report(report, Format::Markdown).unwrap();
+    assert!(markdown.contains(
+        "| 🔥 **Threshold exceeded** | **15** (125.00%) | 14 | `XNGFeed.FeedViewController.(showComboxEntryView in _494CFAE549D032F287E30AAB63618902)() -> ()` | [92620314u](https://appcenter.ms/orgs/org/apps/app/crashes/errors/92620314u/overview) |"
+    ));
+    assert!(markdown.contains("|  | 10 (83.33%) | 4 | `BITPLCrashReportMachExceptionInfo codes` |"));
+    assert_eq!(markdown.matches("<details>").count(), 2);
+}
+
+#[test]
+fn test_markdown_report_escapes_pipes_in_table_cells() {
+    let reporter = CrashReporter::builder("abc", "org", "app").build().unwrap();
+    let mut report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+    report.crash_list.crashes[1].exception_method = Some("operator||".to_string());
+
+    let markdown = reporter.render_report(report, Format::Markdown).unwrap();
+    assert!(markdown.contains("| 10 | 4 | `BITPLCrashReportMachExceptionInfo operator\\|\\|` |"));
+}
+
+#[test]
+fn test_html_report_highlights_crashes_exceeding_the_threshold() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .threshold(12)
+        .build()
+        .unwrap();
+    let report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+
+    let html = reporter.render_report(report, Format::Html).unwrap();
+    assert_eq!(html.matches(r#"<tr class="exceeded">"#).count(), 1);
+    assert!(html.contains("<code>XNGFeed.FeedViewController.(showComboxEntryView in _494CFAE549D032F287E30AAB63618902)() -&gt; ()</code>"));
+    assert!(html.ends_with("</html>\n"));
+}
+
+#[test]
+fn test_csv_report_writes_the_selected_columns_per_crash() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .threshold(12)
+        .format(Format::Csv)
+        .columns(vec![
+            CsvColumn::ErrorGroupId,
+            CsvColumn::Count,
+            CsvColumn::ExceptionMessage,
+            CsvColumn::ThresholdExceeded,
+        ])
+        .build()
+        .unwrap();
+    let report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+
+    let csv = reporter.render_report(report, Format::Csv).unwrap();
+    assert_eq!(
+        csv,
+        "error_group_id,count,exception_message,threshold_exceeded
+92620314u,15,Selector name found in current argument registers: delegate,true
+1549869227u,10,*** -[__NSArrayM insertObject:atIndex:]: object cannot be nil,false
+"
+    );
+}
+
+#[test]
+fn test_report_formatting_uses_the_custom_template_and_partials() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .template("{{#each errorGroups}}{{> crash}}{{/each}}{{> signature}}")
+        .empty_template("No crashes in v{{version}}.")
+        .partial("crash", "{{count}}x {{errorGroupId}}\n")
+        .partial("signature", "-- {{organization}}")
+        .build()
+        .unwrap();
+
+    let report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert_eq!(formatted_report, "15x 92620314u\n10x 1549869227u\n-- org");
+
+    let report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/no_crashes.json",
+    );
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert_eq!(formatted_report, "No crashes in vversion.");
+}
+
+#[test]
+fn test_only_html_reports_are_escaped() {
+    let reporter = CrashReporter::builder("abc", "R&D <Mobile>", "app")
+        .template("{{organization}}")
+        .build()
+        .unwrap();
+    let report = || {
+        utils::test_helper::TestHelper::report_from_json(
+            "src/json_parsing/test_fixtures/two_crashes.json",
+        )
+    };
+
+    let text = reporter.render_report(report(), Format::Text).unwrap();
+    assert_eq!(text, "R&D <Mobile>");
+    let markdown = reporter.render_report(report(), Format::Markdown).unwrap();
+    assert_eq!(markdown, "R&D <Mobile>");
+    let html = reporter.render_report(report(), Format::Html).unwrap();
+    assert_eq!(html, "R&amp;D &lt;Mobile&gt;");
+}
+
+#[test]
+fn test_custom_templates_can_use_the_helpers() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .template("{{#each errorGroups}}{{pluralize count \"crash\" \"crashes\"}} since {{format_date firstOccurrence \"%d.%m.\"}}: {{appcenter_link errorGroupId}}\n{{/each}}")
+        .build()
+        .unwrap();
+    let report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert!(formatted_report.starts_with(
+        "15 crashes since 16.05.: https://appcenter.ms/orgs/org/apps/app/crashes/errors/92620314u/overview\n"
+    ));
+}
+
+#[test]
+fn test_invalid_templates_are_reported_as_errors() {
+    let result = CrashReporter::builder("abc", "org", "app")
+        .template("{{#each errorGroups}}{{count}}")
+        .build();
+    assert!(matches!(result, Err(Error::Template(_))));
+
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .template("{{#each errorGroups}}{{shout count}}{{/each}}")
+        .build()
+        .unwrap();
+    let report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+    assert!(matches!(
+        reporter.format_report(report),
+        Err(Error::Template(_))
+    ));
+}
+
+#[test]
+fn test_columns_require_the_csv_or_tsv_format() {
+    let result = CrashReporter::builder("abc", "org", "app")
+        .columns(vec![CsvColumn::Count])
+        .build();
+    assert!(matches!(result, Err(Error::InvalidOptions(_))));
+}
+
+#[test]
+fn test_comparisons_can_not_be_limited() {
+    let result = CrashReporter::builder("abc", "org", "app")
+        .compare_to("1.0")
+        .error_group_limit(10)
+        .build();
+    assert!(matches!(result, Err(Error::InvalidOptions(_))));
+}
+
+#[test]
+fn test_report_formatting_shows_the_state_of_each_crash() {
+    let reporter = CrashReporter::builder("abc", "org", "app").build().unwrap();
+    let mut report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+    report.crash_list.crashes[1].state = Some(model::ErrorGroupState::Closed);
+
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert_eq!(formatted_report.matches("State:   Open\n").count(), 1);
+    assert_eq!(formatted_report.matches("State:   Closed\n").count(), 1);
+}
+
+#[test]
+fn test_report_formatting_flags_reopened_crashes() {
+    let api = api::MockAPI::with_crashes_json(utils::test_helper::TestHelper::fixture(
+        "src/json_parsing/test_fixtures/closed_crashes.json",
+    ));
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .version("7.41.3")
+        .time_window(TimeWindow::new(
+            "2019-05-15T00:00:00Z".parse().unwrap(),
+            "2019-05-20T00:00:00Z".parse().unwrap(),
+        ))
+        .api(Box::new(api))
+        .build()
+        .unwrap();
+
+    let formatted_report = reporter
+        .format_report(reporter.fetch_report().unwrap())
+        .unwrap();
+    assert_eq!(formatted_report.matches("!! REOPENED !!").count(), 1);
+    assert!(formatted_report.contains(
+        "!! REOPENED !! Closed in AppCenter, but crashed again.
+
+15 times in 7.41.3 (10167)"
+    ));
+    assert_eq!(formatted_report.matches("State:   Closed\n").count(), 2);
+
+    let json: serde_json::Value = serde_json::from_str(
+        &reporter
+            .render_report(reporter.fetch_report().unwrap(), Format::Json)
+            .unwrap(),
+    )
+    .unwrap();
+    let reopened: Vec<&serde_json::Value> = json["crashes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|crash| &crash["reopened"])
+        .collect();
+    assert_eq!(reopened, vec![true, false, false]);
+}
+
+#[test]
+fn test_report_formatting_includes_stacktraces() {
+    let api = api::MockAPI::with_two_crashes().and_stacktrace();
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .version("7.41.3")
+        .stacktrace_frames(3)
+        .api(Box::new(api))
+        .build()
+        .unwrap();
+
+    let report = reporter.fetch_report().unwrap();
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert!(formatted_report.contains(
+        "Stack trace, app code marked with >:
+  __exceptionPreprocess
+  objc_exception_throw
+> -[XNGProfileViewController configureWithProfile:] (XNGProfileViewController.m:142)
+More on AppCenter:"
+    ));
+}
+
+#[test]
+fn test_stacktraces_are_only_fetched_for_shown_crashes() {
+    let reporter = CrashReporter::builder("abc", "org", "app")
+        .version("7.41.3")
+        .use_arithmetic_mean(true)
+        .stacktrace_frames(3)
+        .api(Box::new(api::MockAPI::with_two_crashes().and_stacktrace()))
+        .build()
+        .unwrap();
+
+    let report = reporter.fetch_report().unwrap();
+    let crashes = &report.crash_list.crashes;
+    assert!(crashes[0].stacktrace.is_some());
+    assert!(crashes[1].stacktrace.is_none());
+}
+
 #[test]
 //Formats a crash report including a threshold value
 fn test_report_formatting_supports_threshold() {
-    let reporter = CrashReporter::with_token(
-        "abc",
-        "org name",
-        "app id",
-        None,
-        None,
-        Some(300),
-        false,
-        false,
-        false,
-    );
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .threshold(300)
+        .build()
+        .unwrap();
     let report = utils::test_helper::TestHelper::report_from_json(
         "src/json_parsing/test_fixtures/two_crashes.json",
     );
-    let formatted_report = reporter.format_report(report);
-    assert_eq!(formatted_report.chars().count(), 1412)
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert_eq!(formatted_report.chars().count(), 1674)
 }
 
 #[test]
-fn test_filtering_out_errors() {
-    let reporter = CrashReporter::with_token(
-        "abc",
-        "org name",
-        "app id",
-        None,
-        None,
-        Some(300),
-        false,
-        false,
-        true,
+fn test_report_formatting_supports_version_diff() {
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .build()
+        .unwrap();
+    let base = utils::test_helper::TestHelper::crash_list_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
     );
+    let mut report = utils::test_helper::TestHelper::report_from_json(
+        "src/json_parsing/test_fixtures/paginated_crashes_page_1.json",
+    );
+    report.diff = Some(model::VersionDiff::between(
+        "1.0",
+        &base,
+        &report.version,
+        &report.crash_list,
+        10.0,
+    ));
+
+    let formatted_report = reporter.format_report(report).unwrap();
+    assert!(formatted_report.contains("Compared to v1.0"));
+    assert_eq!(formatted_report.matches("\nNEW ").count(), 2);
+    assert_eq!(formatted_report.matches("\nRESOLVED ").count(), 1);
+}
+
+#[test]
+fn test_trend_formatting() {
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .filter_out_errors(true)
+        .build()
+        .unwrap();
+    let base = utils::test_helper::TestHelper::crash_list_from_json(
+        "src/json_parsing/test_fixtures/two_crashes.json",
+    );
+    let crash_list = utils::test_helper::TestHelper::crash_list_from_json(
+        "src/json_parsing/test_fixtures/paginated_crashes_page_1.json",
+    );
+    let trend = model::Trend::from_reports(&[
+        Report::new("1.0".to_string(), base),
+        Report::new("1.1".to_string(), crash_list),
+    ]);
+
+    let formatted_trend = reporter.format_trend(trend, Format::Text).unwrap();
+    assert!(formatted_trend.contains("This is the crash trend of v1.0, v1.1"));
+    assert!(formatted_trend.contains("\nCrashes: "));
+    assert!(formatted_trend.contains("\nDevices: 0 → "));
+    assert!(!formatted_trend.contains("Congratulations"));
+}
+
+#[test]
+fn test_trends_can_only_be_formatted_as_text_or_json() {
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .build()
+        .unwrap();
+    let trend = || {
+        model::Trend::from_reports(&[Report::new(
+            "1.0".to_string(),
+            utils::test_helper::TestHelper::crash_list_from_json(
+                "src/json_parsing/test_fixtures/two_crashes.json",
+            ),
+        )])
+    };
+
+    for format in [Format::Markdown, Format::Html, Format::Csv, Format::Tsv] {
+        assert!(matches!(
+            reporter.format_trend(trend(), format),
+            Err(Error::InvalidOptions(_))
+        ));
+    }
+}
+
+#[test]
+fn test_filtering_out_errors() {
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .threshold(300)
+        .filter_out_errors(true)
+        .build()
+        .unwrap();
     let report = utils::test_helper::TestHelper::report_from_json(
         "src/json_parsing/test_fixtures/api_response.json",
     );
@@ -454,17 +1847,11 @@ fn test_filtering_out_errors() {
 
 #[test]
 fn test_report_formatting_supports_filtering_out_errors() {
-    let reporter = CrashReporter::with_token(
-        "abc",
-        "org name",
-        "app id",
-        None,
-        None,
-        Some(300),
-        false,
-        false,
-        true, // <- switch to omit-errors from report
-    );
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .threshold(300)
+        .filter_out_errors(true) // <- switch to omit-errors from report
+        .build()
+        .unwrap();
     let report = utils::test_helper::TestHelper::report_from_json(
         "src/json_parsing/test_fixtures/api_response.json",
     );
@@ -473,24 +1860,18 @@ fn test_report_formatting_supports_filtering_out_errors() {
     let data = crash_list_json.as_object_mut().unwrap();
     let amount_of_crashes_in_fixture = data.get("errorGroups").unwrap().as_array().unwrap().len();
 
-    let formatted_report = reporter.format_report(report);
+    let formatted_report = reporter.format_report(report).unwrap();
     let amount_of_formatted_crashes = formatted_report.matches("More on AppCenter").count();
     assert!(amount_of_crashes_in_fixture != amount_of_formatted_crashes);
 }
 
 #[test]
 fn test_report_formatting_does_not_filter_out_errors() {
-    let reporter = CrashReporter::with_token(
-        "abc",
-        "org name",
-        "app id",
-        None,
-        None,
-        Some(300),
-        false,
-        false,
-        false, // <- switch to not omit errors from report
-    );
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .threshold(300)
+        .filter_out_errors(false) // <- switch to not omit errors from report
+        .build()
+        .unwrap();
     let report = utils::test_helper::TestHelper::report_from_json(
         "src/json_parsing/test_fixtures/api_response.json",
     );
@@ -499,27 +1880,20 @@ fn test_report_formatting_does_not_filter_out_errors() {
     let data = crash_list_json.as_object_mut().unwrap();
     let amount_of_crashes_in_fixture = data.get("errorGroups").unwrap().as_array().unwrap().len();
 
-    let formatted_report = reporter.format_report(report);
+    let formatted_report = reporter.format_report(report).unwrap();
     let amount_of_formatted_crashes = formatted_report.matches("More on AppCenter").count();
     assert_eq!(amount_of_crashes_in_fixture, amount_of_formatted_crashes);
 }
 
 #[test]
 fn test_report_template_if_no_crash_exists() {
-    let reporter = CrashReporter::with_token(
-        "abc",
-        "org name",
-        "app id",
-        None,
-        None,
-        Some(300),
-        false,
-        false,
-        false,
-    );
+    let reporter = CrashReporter::builder("abc", "org name", "app id")
+        .threshold(300)
+        .build()
+        .unwrap();
     let report = utils::test_helper::TestHelper::report_from_json(
         "src/json_parsing/test_fixtures/no_crashes.json",
     );
-    let formatted_report = reporter.format_report(report);
+    let formatted_report = reporter.format_report(report).unwrap();
     assert_eq!(formatted_report.chars().count(), 218)
 }
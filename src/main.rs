@@ -1,11 +1,35 @@
+use chrono::{DateTime, NaiveDate, TimeDelta, Utc};
 use clap::{crate_authors, crate_version};
-use clap::{App, Arg, ArgMatches};
-use recrep::CrashReporter;
+use clap::{App, Arg, ArgGroup, ArgMatches};
+use recrep::api::{AppCenter, CachingAPI, RecordingAPI, ReplayAPI, RetryPolicy, API};
+use recrep::model::{ErrorGroupState, TimeWindow, VersionOrdering};
+use recrep::{CrashReporter, CsvColumn, Error, Format};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// The report was created and no crash exceeds the threshold.
+const EXIT_SUCCESS: i32 = 0;
+
+/// The options or templates are invalid, or the report could not be written.
+const EXIT_FAILURE: i32 = 1;
+
+/// The report was created, but crashes exceed the threshold or crash-free rates fall below their
+/// minimum, and `--fail-on-threshold` is set.
+const EXIT_THRESHOLD_EXCEEDED: i32 = 2;
+
+/// Fetching data from AppCenter failed, e.g. because of an invalid token.
+const EXIT_API_FAILURE: i32 = 3;
+
+/// There is no version to create the report for.
+const EXIT_NO_VERSION_FOUND: i32 = 4;
 
 fn main() {
     let app = app();
     let matches = matches_for_app(app);
-    let token = matches.value_of("token").expect("Token is required.");
+    // the token is only optional when replaying recorded responses
+    let token = matches.value_of("token").unwrap_or_default();
     let version = matches.value_of("version");
     let outfile = matches.value_of("outfile");
     let organization = matches
@@ -18,23 +42,351 @@ fn main() {
     let crash_threshold = matches.value_of("threshold");
     let use_arithmetic_mean = matches.is_present("arithmetic-mean");
     let filter_out_errors = matches.is_present("omit-errors");
-    let version = version.map(String::from);
-    let group = distribution_group.map(String::from);
-    let crash_threshold =
-        crash_threshold.map(|x| x.parse::<u64>().expect("Invalid number provided"));
+    let crash_threshold = crash_threshold.map(|threshold| {
+        parse_number::<u64>(threshold, "The threshold must be a number greater than 0.")
+    });
     let show_os_information = matches.is_present("show-operating-systems");
-    let crash_reporter = CrashReporter::with_token(
-        token,
-        organization,
-        application,
-        version,
-        group,
-        crash_threshold,
-        use_arithmetic_mean,
-        show_os_information,
-        filter_out_errors,
+    let fail_on_threshold = matches.is_present("fail-on-threshold");
+    let error_group_limit = matches.value_of("limit").map(|limit| {
+        parse_number::<usize>(
+            limit,
+            "The error group limit must be a number greater than 0.",
+        )
+    });
+
+    let api = api_for_matches(&matches, token);
+
+    let parallel_requests = parse_number(
+        matches
+            .value_of("parallel-requests")
+            .expect("Parallel requests have a default value"),
+        "The amount of parallel requests must be a number greater than 0.",
     );
-    crash_reporter.create_report(outfile);
+
+    let version_ordering = match matches
+        .value_of("version-ordering")
+        .expect("Version ordering has a default value")
+        .parse::<VersionOrdering>()
+    {
+        Ok(version_ordering) => version_ordering,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let format = match matches
+        .value_of("format")
+        .expect("Format has a default value")
+        .parse::<Format>()
+    {
+        Ok(format) => format,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let mut builder = CrashReporter::builder(token, organization, application)
+        .api(api)
+        .format(format)
+        .version_ordering(version_ordering)
+        .parallel_requests(parallel_requests)
+        .use_arithmetic_mean(use_arithmetic_mean)
+        .show_os_information(show_os_information)
+        .break_down_by_build(matches.is_present("per-build"))
+        .filter_out_errors(filter_out_errors);
+    if let Some(version) = version {
+        builder = builder.version(version);
+    }
+    if let Some(states) = matches.values_of("status") {
+        match states
+            .map(|state| state.parse::<ErrorGroupState>())
+            .collect::<Result<Vec<ErrorGroupState>, Error>>()
+        {
+            Ok(states) => builder = builder.states(states),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(EXIT_FAILURE);
+            }
+        }
+    }
+    if let Some(columns) = matches.values_of("columns") {
+        match columns
+            .map(|column| column.parse::<CsvColumn>())
+            .collect::<Result<Vec<CsvColumn>, Error>>()
+        {
+            Ok(columns) => builder = builder.columns(columns),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(EXIT_FAILURE);
+            }
+        }
+    }
+    match since_for_matches(&matches) {
+        Ok(Some(since)) => builder = builder.since(since),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(EXIT_FAILURE);
+        }
+    }
+    match time_window_for_matches(&matches) {
+        Ok(Some(time_window)) => builder = builder.time_window(time_window),
+        Ok(None) => {}
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(EXIT_FAILURE);
+        }
+    }
+    if let Some(build) = matches.value_of("build") {
+        builder = builder.app_build(build);
+    }
+    if let Some(group) = distribution_group {
+        builder = builder.distribution_group(group);
+    }
+    if let Some(threshold) = crash_threshold {
+        builder = builder.threshold(threshold);
+    }
+    if let Some(minimum) = matches.value_of("min-crash-free-users") {
+        builder = builder.min_crash_free_users(parse_number(
+            minimum,
+            "The minimum crash-free rates must be percentages between 0 and 100.",
+        ));
+    }
+    if let Some(minimum) = matches.value_of("min-crash-free-sessions") {
+        builder = builder.min_crash_free_sessions(parse_number(
+            minimum,
+            "The minimum crash-free rates must be percentages between 0 and 100.",
+        ));
+    }
+    if let Some(frames) = matches.value_of("stacktraces") {
+        builder = builder.stacktrace_frames(parse_number(
+            frames,
+            "The amount of stack trace frames must be a number greater than 0.",
+        ));
+    }
+    if let Some(limit) = error_group_limit {
+        builder = builder.error_group_limit(limit);
+    }
+    if let Some(base_version) = matches.value_of("compare-to") {
+        builder = builder
+            .compare_to(base_version)
+            .regression_threshold(parse_number(
+                matches
+                    .value_of("regression-threshold")
+                    .expect("Regression threshold has a default value"),
+                "The regression threshold must be a percentage of at least 0.",
+            ));
+    }
+    if let Some(path) = matches.value_of("template") {
+        builder = builder.template(&read_template(path));
+    }
+    if let Some(path) = matches.value_of("empty-template") {
+        builder = builder.empty_template(&read_template(path));
+    }
+    for path in matches.values_of("partial").into_iter().flatten() {
+        let name = Path::new(path)
+            .file_stem()
+            .and_then(|name| name.to_str())
+            .unwrap_or(path);
+        builder = builder.partial(name, &read_template(path));
+    }
+    let crash_reporter = match builder.build() {
+        Ok(crash_reporter) => crash_reporter,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(EXIT_FAILURE);
+        }
+    };
+
+    if matches.is_present("list-builds") {
+        let exit_code = match crash_reporter.list_builds(outfile) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(e) => {
+                eprintln!("Failed to list the builds with error: {}", e);
+                exit_code_for_error(&e)
+            }
+        };
+        process::exit(exit_code);
+    }
+
+    if let Some(versions) = matches.value_of("trend") {
+        let versions = parse_number(
+            versions,
+            "The amount of versions must be a number greater than 0.",
+        );
+        let exit_code = match crash_reporter.create_trend_report(versions, outfile) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(e) => {
+                eprintln!("Failed to create the crash trend with error: {}", e);
+                exit_code_for_error(&e)
+            }
+        };
+        process::exit(exit_code);
+    }
+
+    let exit_code = match crash_reporter.create_report(outfile) {
+        Ok(threshold_violations) if fail_on_threshold && threshold_violations > 0 => {
+            eprintln!(
+                "{} crash(es) or crash-free rate(s) exceeded the threshold.",
+                threshold_violations
+            );
+            EXIT_THRESHOLD_EXCEEDED
+        }
+        Ok(_) => EXIT_SUCCESS,
+        Err(e) => {
+            eprintln!("Failed to create the crash report with error: {}", e);
+            exit_code_for_error(&e)
+        }
+    };
+    process::exit(exit_code);
+}
+
+/// Creates the API the crashes are fetched from. AppCenter requests are retried and cached, unless
+/// recorded responses are replayed.
+fn api_for_matches(matches: &ArgMatches, token: &str) -> Box<dyn API> {
+    if let Some(replay_directory) = matches.value_of("replay") {
+        return Box::new(ReplayAPI::from_directory(PathBuf::from(replay_directory)));
+    }
+
+    let retry_policy = RetryPolicy {
+        max_retries: parse_number(
+            matches
+                .value_of("retries")
+                .expect("Retries have a default value"),
+            "The amount of retries must be a number of at least 0.",
+        ),
+        initial_backoff: Duration::from_millis(parse_number(
+            matches
+                .value_of("retry-backoff")
+                .expect("Retry backoff has a default value"),
+            "The retry backoff must be a number of milliseconds.",
+        )),
+        ..RetryPolicy::default()
+    };
+    let api = AppCenter::new(token.to_string())
+        .with_retry_policy(retry_policy)
+        .with_base_url(
+            matches
+                .value_of("api-base-url")
+                .expect("API base url has a default value"),
+        )
+        .with_api_version(
+            matches
+                .value_of("api-version")
+                .expect("API version has a default value"),
+        );
+    let cache_namespace = api.cache_namespace();
+    let mut api: Box<dyn API> = Box::new(api);
+    if !matches.is_present("no-cache") {
+        let cache_directory = matches
+            .value_of("cache-dir")
+            .map_or_else(CachingAPI::default_directory, PathBuf::from)
+            .join(cache_namespace);
+        let message = "The cache time to live must be a number of minutes.";
+        let cache_ttl = parse_number::<u64>(
+            matches
+                .value_of("cache-ttl")
+                .expect("Cache time to live has a default value"),
+            message,
+        )
+        .checked_mul(60)
+        .unwrap_or_else(|| {
+            eprintln!("{}", Error::InvalidOptions(message));
+            process::exit(EXIT_FAILURE);
+        });
+        api = Box::new(CachingAPI::wrapping(
+            api,
+            cache_directory,
+            Duration::from_secs(cache_ttl),
+        ));
+    }
+
+    if let Some(record_directory) = matches.value_of("record") {
+        api = Box::new(RecordingAPI::wrapping(api, PathBuf::from(record_directory)));
+    }
+    api
+}
+
+/// The start of the date range to report on, given either as date or as amount of days before now.
+fn since_for_matches(matches: &ArgMatches) -> Result<Option<DateTime<Utc>>, Error> {
+    if let Some(since) = matches.value_of("since") {
+        return parse_time(since).map(Some);
+    }
+    if let Some(days) = matches.value_of("last-days") {
+        let days = days.parse::<u32>().map_err(|_| {
+            Error::InvalidOptions("The amount of days must be a number of at least 0.")
+        })?;
+        return Ok(Some(Utc::now() - TimeDelta::days(days.into())));
+    }
+    Ok(None)
+}
+
+/// The window the crashes are counted in, given either by its start and optional end or as amount
+/// of hours before now.
+fn time_window_for_matches(matches: &ArgMatches) -> Result<Option<TimeWindow>, Error> {
+    if let Some(hours) = matches.value_of("last-hours") {
+        let hours =
+            hours
+                .parse::<u32>()
+                .ok()
+                .filter(|hours| *hours > 0)
+                .ok_or(Error::InvalidOptions(
+                    "The amount of hours must be a number greater than 0.",
+                ))?;
+        return Ok(Some(TimeWindow::last(TimeDelta::hours(hours.into()))));
+    }
+    match matches.value_of("start") {
+        Some(start) => {
+            let end = match matches.value_of("end") {
+                Some(end) => parse_time(end)?,
+                None => Utc::now(),
+            };
+            Ok(Some(TimeWindow::new(parse_time(start)?, end)))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Parses a point in time given either as date, e.g. `2026-10-01`, or as RFC 3339 timestamp, e.g.
+/// `2026-10-01T12:00:00Z`.
+fn parse_time(time: &str) -> Result<DateTime<Utc>, Error> {
+    if let Ok(date) = NaiveDate::parse_from_str(time, "%Y-%m-%d") {
+        return Ok(date.and_time(Default::default()).and_utc());
+    }
+    DateTime::parse_from_rfc3339(time)
+        .map(|time| time.with_timezone(&Utc))
+        .map_err(|_| {
+            Error::InvalidOptions("Dates must be given as `YYYY-MM-DD` or as RFC 3339 timestamp.")
+        })
+}
+
+/// Parses a numeric option, exiting with the message as invalid options if it isn't a number.
+fn parse_number<T: FromStr>(value: &str, message: &'static str) -> T {
+    value.parse().unwrap_or_else(|_| {
+        eprintln!("{}", Error::InvalidOptions(message));
+        process::exit(EXIT_FAILURE);
+    })
+}
+
+/// Reads a template or partial, exiting if the file can't be read.
+fn read_template(path: &str) -> String {
+    match std::fs::read_to_string(path) {
+        Ok(template) => template,
+        Err(e) => {
+            eprintln!("💥 Failed to read the template {}: {}", path, e);
+            process::exit(EXIT_FAILURE);
+        }
+    }
+}
+
+fn exit_code_for_error(error: &Error) -> i32 {
+    match error {
+        Error::NoVersionFound => EXIT_NO_VERSION_FOUND,
+        Error::Io(_) | Error::InvalidOptions(_) | Error::Template(_) => EXIT_FAILURE,
+        _ => EXIT_API_FAILURE,
+    }
 }
 
 fn app<'a, 'b>() -> App<'a, 'b> {
@@ -45,6 +397,7 @@ fn app<'a, 'b>() -> App<'a, 'b> {
 }
 
 fn matches_for_app<'a>(app: App<'a, '_>) -> ArgMatches<'a> {
+    let column_names: Vec<&str> = CsvColumn::ALL.iter().map(|column| column.name()).collect();
     app.args(&[
         Arg::with_name("token")
             .help("The AppCenter API token")
@@ -52,13 +405,61 @@ fn matches_for_app<'a>(app: App<'a, '_>) -> ArgMatches<'a> {
             .short("t")
             .long("token")
             .env("RECREP_APPCENTER_API_TOKEN")
-            .required(true),
+            .required_unless("replay"),
         Arg::with_name("version")
             .help("The app version. If none is specified, the latest available version will be picked - be aware that the latest version might not have crashes yet.")
             .takes_value(true)
             .short("v")
             .long("version")
             .required(false),
+        Arg::with_name("since")
+            .help("Combine the crashes of all versions uploaded since the given date, e.g. `2026-10-01`, into one report, showing how often each crash occurred in each version. Respects `--group`.")
+            .takes_value(true)
+            .long("since")
+            .required(false)
+            .conflicts_with_all(&["version", "last-days", "trend", "list-builds", "per-build"]),
+        Arg::with_name("last-days")
+            .help("Combine the crashes of all versions uploaded within the given amount of days into one report, like `--since`.")
+            .takes_value(true)
+            .long("last-days")
+            .required(false)
+            .conflicts_with_all(&["version", "trend", "list-builds", "per-build"]),
+        Arg::with_name("start")
+            .help("Only count the crashes which occurred since the given date or RFC 3339 timestamp, e.g. `2026-10-01T12:00:00Z`. Defaults to the last 30 days.")
+            .takes_value(true)
+            .long("start")
+            .required(false),
+        Arg::with_name("end")
+            .help("Only count the crashes which occurred until the given date or RFC 3339 timestamp. Defaults to now. Requires `--start`.")
+            .takes_value(true)
+            .long("end")
+            .required(false)
+            .requires("start"),
+        Arg::with_name("last-hours")
+            .help("Only count the crashes which occurred within the given amount of hours, e.g. `24`.")
+            .takes_value(true)
+            .long("last-hours")
+            .required(false)
+            .conflicts_with("start"),
+        Arg::with_name("build")
+            .help("Report on a single build of the version, e.g. `10167`. Requires `--version`.")
+            .takes_value(true)
+            .short("b")
+            .long("build")
+            .required(false)
+            .requires("version"),
+        Arg::with_name("list-builds")
+            .help("List the builds of the version, or of the latest version, instead of reporting crashes.")
+            .takes_value(false)
+            .long("list-builds")
+            .required(false)
+            .conflicts_with_all(&["build", "trend"]),
+        Arg::with_name("per-build")
+            .help("Break the counts of each crash down per build, if the version has several builds.")
+            .takes_value(false)
+            .long("per-build")
+            .required(false)
+            .conflicts_with("build"),
         Arg::with_name("organization")
             .help("The organization the app belongs to.")
             .takes_value(true)
@@ -83,28 +484,190 @@ fn matches_for_app<'a>(app: App<'a, '_>) -> ArgMatches<'a> {
             .short("g")
             .long("group")
             .required(false),
+        Arg::with_name("version-ordering")
+            .help("How versions are ordered to find the latest one: `upload-date` picks the most recently uploaded version, `semantic` the highest version number, so a hotfix of an older version uploaded later is not picked.")
+            .takes_value(true)
+            .long("version-ordering")
+            .possible_values(&["upload-date", "semantic"])
+            .default_value("upload-date")
+            .required(false),
         Arg::with_name("threshold")
             .help("Set a threshold value to determine a baseline amount of crashes. This max represents 100%. A percentage is shown of how many percent of this maximum is reached for each crash in the crash list. Crashes exceeding the threshold are marked as such.")
             .takes_value(true)
             .short("m")
             .long("threshold")
             .required(false),
+        Arg::with_name("fail-on-threshold")
+            .help("Exit with status 2 if at least one crash exceeds the threshold, or a crash-free rate falls below its minimum. Requires `--threshold`, `--min-crash-free-users` or `--min-crash-free-sessions`.")
+            .takes_value(false)
+            .long("fail-on-threshold")
+            .required(false)
+            .requires("thresholds"),
+        Arg::with_name("min-crash-free-users")
+            .help("The percentage of users without crashes the version must reach, e.g. `99.5`. Lower rates are marked in the report.")
+            .takes_value(true)
+            .long("min-crash-free-users")
+            .required(false),
+        Arg::with_name("min-crash-free-sessions")
+            .help("The percentage of sessions without crashes the version must reach, e.g. `99.9`. Lower rates are marked in the report.")
+            .takes_value(true)
+            .long("min-crash-free-sessions")
+            .required(false),
         Arg::with_name("arithmetic-mean")
             .help("Use the arithmetic mean as threshold value, and only show crashes exceeding this value. This flag is incompatible with `--threshold`.")
             .takes_value(false)
             .long("arithmetic-mean")
             .required(false)
             .conflicts_with("threshold"),
+        Arg::with_name("compare-to")
+            .help("Compare the crashes with the ones of the given base version, e.g. the previous release, and list new, regressed, improved and resolved crashes.")
+            .takes_value(true)
+            .long("compare-to")
+            .required(false)
+            .conflicts_with("limit"),
+        Arg::with_name("regression-threshold")
+            .help("The percentage a crash count has to change by compared to the base version to count as regressed or improved.")
+            .takes_value(true)
+            .long("regression-threshold")
+            .default_value("10")
+            .required(false),
+        Arg::with_name("trend")
+            .help("Report how each crash developed across the given amount of latest versions instead of reporting on a single version. Respects `--group`.")
+            .takes_value(true)
+            .long("trend")
+            .required(false)
+            .conflicts_with_all(&["version", "compare-to", "thresholds", "arithmetic-mean"]),
+        Arg::with_name("format")
+            .help("The output format of the report or trend: `text` to read it, `markdown` for wiki pages, `html` for HTML mails, `json` to process it with other tools, `csv` or `tsv` for spreadsheets. Trends can only be written as text or json.")
+            .takes_value(true)
+            .long("format")
+            .possible_values(&["text", "markdown", "html", "json", "csv", "tsv"])
+            .default_value("text")
+            .required(false),
+        Arg::with_name("template")
+            .help("A Handlebars template file replacing the newsletter of the text, markdown and html formats. See the readme for the available variables.")
+            .takes_value(true)
+            .long("template")
+            .required(false)
+            .conflicts_with_all(&["trend", "list-builds"]),
+        Arg::with_name("empty-template")
+            .help("A Handlebars template file replacing the newsletter of versions without crashes. Defaults to `--template`.")
+            .takes_value(true)
+            .long("empty-template")
+            .required(false)
+            .conflicts_with_all(&["trend", "list-builds"]),
+        Arg::with_name("partial")
+            .help("A Handlebars partial file the templates can include by its file name without extension, e.g. `{{> footer}}` for `footer.hbs`. Can be given several times.")
+            .takes_value(true)
+            .long("partial")
+            .multiple(true)
+            .number_of_values(1)
+            .required(false),
+        Arg::with_name("columns")
+            .help("The columns of the csv or tsv format, in the given order, e.g. `error_group_id,count,exception_type`. Defaults to all columns.")
+            .takes_value(true)
+            .long("columns")
+            .use_delimiter(true)
+            .possible_values(&column_names)
+            .required(false),
         Arg::with_name("show-operating-systems")
             .help("Show the operating systems affected for each crash.")
             .takes_value(false)
             .long("show-operating-systems")
             .required(false),
+        Arg::with_name("status")
+            .help("Only report error groups in the given states, e.g. `open,reopened` to skip the ones closed or ignored during triage. Closed error groups which crashed again within the reported period count as reopened.")
+            .takes_value(true)
+            .long("status")
+            .use_delimiter(true)
+            .possible_values(&["open", "reopened", "closed", "ignored"])
+            .required(false),
+        Arg::with_name("stacktraces")
+            .help("Show the given amount of topmost stack trace frames for each crash, marking the frames of the app's own code.")
+            .takes_value(true)
+            .long("stacktraces")
+            .required(false),
+        Arg::with_name("parallel-requests")
+            .help("The maximum amount of concurrent requests when fetching the operating systems affected by each crash or their stack traces.")
+            .takes_value(true)
+            .long("parallel-requests")
+            .default_value("4")
+            .required(false),
         Arg::with_name("omit-errors")
             .help("Filters out AppCenter \"Crashes\" that are classified as `Error`.")
             .takes_value(false)
             .long("omit-errors")
             .required(false),
+        Arg::with_name("api-base-url")
+            .help("The base url of the AppCenter API. Use it to send requests to a proxy or a local stub server.")
+            .takes_value(true)
+            .long("api-base-url")
+            .env("RECREP_APPCENTER_API_BASE_URL")
+            .default_value(recrep::api::appcenter_api::DEFAULT_BASE_URL)
+            .required(false),
+        Arg::with_name("api-version")
+            .help("The version of the AppCenter API.")
+            .takes_value(true)
+            .long("api-version")
+            .env("RECREP_APPCENTER_API_VERSION")
+            .default_value(recrep::api::appcenter_api::DEFAULT_API_VERSION)
+            .required(false),
+        Arg::with_name("retries")
+            .help("How often a request is retried when AppCenter throttles the requests, has a server error or the network fails.")
+            .takes_value(true)
+            .long("retries")
+            .default_value("3")
+            .required(false),
+        Arg::with_name("retry-backoff")
+            .help("The milliseconds to wait before the first retry. The delay doubles with every further retry, unless AppCenter requests a delay using `Retry-After`.")
+            .takes_value(true)
+            .long("retry-backoff")
+            .default_value("1000")
+            .required(false),
+        Arg::with_name("no-cache")
+            .help("Always request AppCenter instead of using cached responses of previous runs.")
+            .takes_value(false)
+            .long("no-cache")
+            .required(false),
+        Arg::with_name("cache-dir")
+            .help("The directory to cache AppCenter responses in. Defaults to `$XDG_CACHE_HOME/recrep` or `~/.cache/recrep`.")
+            .takes_value(true)
+            .long("cache-dir")
+            .env("RECREP_CACHE_DIR")
+            .required(false)
+            .conflicts_with("no-cache"),
+        Arg::with_name("cache-ttl")
+            .help("The minutes a cached AppCenter response stays valid.")
+            .takes_value(true)
+            .long("cache-ttl")
+            .default_value("60")
+            .required(false),
+        Arg::with_name("record")
+            .help("Writes every AppCenter response alongside its request into the given directory, to be replayed with `--replay`.")
+            .takes_value(true)
+            .long("record")
+            .required(false),
+        Arg::with_name("replay")
+            .help("Answers all requests with the responses recorded into the given directory using `--record`, instead of requesting AppCenter.")
+            .takes_value(true)
+            .long("replay")
+            .required(false)
+            .conflicts_with("record"),
+        Arg::with_name("limit")
+            .help("The maximum amount of error groups to fetch from AppCenter. If none is specified, all error groups of the version are fetched.")
+            .takes_value(true)
+            .short("l")
+            .long("limit")
+            .required(false),
     ])
+    .group(
+        ArgGroup::with_name("thresholds")
+            .args(&[
+                "threshold",
+                "min-crash-free-users",
+                "min-crash-free-sessions",
+            ])
+            .multiple(true),
+    )
     .get_matches()
 }
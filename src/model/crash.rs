@@ -1,7 +1,7 @@
-use crate::model::OperatingSystemCount;
+use crate::model::{BuildCount, ErrorGroupState, OperatingSystemCount, StackFrame, VersionCount};
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Crash {
     #[serde(rename = "exceptionMethod")]
     pub exception_method: Option<String>,
@@ -15,6 +15,18 @@ pub struct Crash {
     #[serde(rename = "firstOccurrence")]
     pub first_occurrence: Option<String>,
 
+    #[serde(rename = "lastOccurrence")]
+    pub last_occurrence: Option<String>,
+
+    /// The kind of exception or signal, e.g. `NSInvalidArgumentException` or `SIGSEGV`.
+    #[serde(rename = "exceptionType")]
+    pub exception_type: Option<String>,
+
+    /// Describes what went wrong, e.g. `*** -[__NSArrayM insertObject:atIndex:]: object cannot be
+    /// nil`.
+    #[serde(rename = "exceptionMessage")]
+    pub exception_message: Option<String>,
+
     #[serde(rename = "exceptionFile")]
     pub exception_file: Option<String>,
 
@@ -30,7 +42,55 @@ pub struct Crash {
     #[serde(rename = "exceptionAppCode")]
     pub exception_app_code: Option<bool>,
 
+    /// The raw code of the frame the exception was thrown in.
+    #[serde(rename = "codeRaw")]
+    pub code_raw: Option<String>,
+
+    /// The frame the exception was thrown in.
+    #[serde(rename = "reasonFrame")]
+    pub reason_frame: Option<StackFrame>,
+
+    /// The state of the error group in AppCenter.
+    pub state: Option<ErrorGroupState>,
+
     pub count: Option<u64>,
 
     pub operating_systems: Option<Vec<OperatingSystemCount>>,
+
+    /// The occurrences per build, if the version has several builds and a breakdown was requested.
+    pub builds: Option<Vec<BuildCount>>,
+
+    /// The occurrences per version, if the report combines several versions.
+    pub versions: Option<Vec<VersionCount>>,
+
+    /// The topmost frames of the representative stack trace, if requested.
+    pub stacktrace: Option<Vec<StackFrame>>,
+
+    /// Whether the error group is closed in AppCenter, but crashed again within the reported
+    /// period. AppCenter doesn't know this, it is derived from the state and the last occurrence.
+    #[serde(default, skip_deserializing)]
+    pub reopened: bool,
+}
+
+impl Crash {
+    /// AppCenter error group ids ending with `u` belong to crashes, all others belong to errors.
+    pub fn is_crash(&self) -> bool {
+        self.error_group_id
+            .as_ref()
+            .is_some_and(|id| id.ends_with('u'))
+    }
+
+    /// The state of the error group. Groups without a state count as open, like in AppCenter.
+    pub fn state(&self) -> ErrorGroupState {
+        self.state.unwrap_or(ErrorGroupState::Open)
+    }
+
+    /// Whether the error group is in the state, counting reopened groups as both closed and
+    /// reopened.
+    pub fn has_state(&self, state: ErrorGroupState) -> bool {
+        match state {
+            ErrorGroupState::Reopened => self.reopened,
+            state => self.state() == state,
+        }
+    }
 }
@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+/// The average share of devices without crashes, as returned by AppCenter.
+#[derive(Debug, Deserialize)]
+pub struct CrashFreeDevices {
+    #[serde(rename = "averagePercentage")]
+    pub average_percentage: f64,
+}
+
+/// The amount of sessions of a single day.
+#[derive(Debug, Deserialize)]
+pub struct SessionCount {
+    pub count: u64,
+}
+
+/// The amount of crashes within a period of time.
+#[derive(Debug, Deserialize)]
+pub struct CrashCount {
+    pub count: u64,
+}
+
+/// How many users and sessions of a version were not affected by crashes.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CrashFreeStatistics {
+    /// The percentage of users, or rather devices, without crashes.
+    pub crash_free_users: f64,
+
+    /// The percentage of sessions without crashes. Unknown if there were no sessions.
+    pub crash_free_sessions: Option<f64>,
+
+    pub sessions: u64,
+
+    pub crashes: u64,
+}
+
+impl CrashFreeStatistics {
+    /// Derives the crash-free sessions from the amount of sessions and crashes, assuming that a
+    /// session ends with its first crash.
+    ///
+    /// ```
+    /// use recrep::model::{CrashCount, CrashFreeDevices, CrashFreeStatistics, SessionCount};
+    ///
+    /// let statistics = CrashFreeStatistics::new(
+    ///     &CrashFreeDevices { average_percentage: 99.5 },
+    ///     &[SessionCount { count: 300 }, SessionCount { count: 700 }],
+    ///     &CrashCount { count: 5 },
+    /// );
+    /// assert_eq!(statistics.crash_free_sessions, Some(99.5));
+    /// ```
+    pub fn new(
+        crash_free_devices: &CrashFreeDevices,
+        session_counts: &[SessionCount],
+        crash_count: &CrashCount,
+    ) -> CrashFreeStatistics {
+        let sessions: u64 = session_counts.iter().map(|sessions| sessions.count).sum();
+        let crash_free_sessions = if sessions == 0 {
+            None
+        } else {
+            let crashes = crash_count.count.min(sessions);
+            Some((sessions - crashes) as f64 / sessions as f64 * 100.0)
+        };
+
+        CrashFreeStatistics {
+            crash_free_users: crash_free_devices.average_percentage,
+            crash_free_sessions,
+            sessions,
+            crashes: crash_count.count,
+        }
+    }
+}
@@ -1,10 +1,15 @@
 use crate::model::Crash;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CrashList {
     #[serde(rename = "errorGroups")]
     pub crashes: Vec<Crash>,
+
+    /// Link to the next page of error groups. AppCenter only sets it when there are more error
+    /// groups available than returned in this page.
+    #[serde(rename = "nextLink", skip_serializing)]
+    pub next_link: Option<String>,
 }
 
 // pub fn from_json() -> impl API {
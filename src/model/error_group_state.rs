@@ -0,0 +1,93 @@
+use crate::error::{Error, Result};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// The triage state of an error group in AppCenter.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ErrorGroupState {
+    #[default]
+    Open,
+
+    /// Closed in AppCenter, yet crashed within the reported period. AppCenter keeps such groups
+    /// closed, so this state only selects them, see `Crash::reopened`.
+    Reopened,
+
+    Closed,
+
+    Ignored,
+
+    /// Any state AppCenter may add in the future.
+    #[serde(other)]
+    Unknown,
+}
+
+impl FromStr for ErrorGroupState {
+    type Err = Error;
+
+    /// Parses the name of a state as given on the command line.
+    ///
+    /// ```
+    /// use recrep::model::ErrorGroupState;
+    ///
+    /// assert_eq!("Closed".parse::<ErrorGroupState>().unwrap(), ErrorGroupState::Closed);
+    /// assert_eq!("reopened".parse::<ErrorGroupState>().unwrap(), ErrorGroupState::Reopened);
+    /// assert!("fixed".parse::<ErrorGroupState>().is_err());
+    /// ```
+    fn from_str(state: &str) -> Result<ErrorGroupState> {
+        match state.to_lowercase().as_str() {
+            "open" => Ok(ErrorGroupState::Open),
+            "reopened" => Ok(ErrorGroupState::Reopened),
+            "closed" => Ok(ErrorGroupState::Closed),
+            "ignored" => Ok(ErrorGroupState::Ignored),
+            _ => Err(Error::InvalidOptions(
+                "The status must be one of `open`, `reopened`, `closed` or `ignored`.",
+            )),
+        }
+    }
+}
+
+impl fmt::Display for ErrorGroupState {
+    /// Writes the name of the state as AppCenter and the json format spell it.
+    ///
+    /// ```
+    /// use recrep::model::ErrorGroupState;
+    ///
+    /// assert_eq!(ErrorGroupState::Ignored.to_string(), "Ignored");
+    /// ```
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            ErrorGroupState::Open => "Open",
+            ErrorGroupState::Reopened => "Reopened",
+            ErrorGroupState::Closed => "Closed",
+            ErrorGroupState::Ignored => "Ignored",
+            ErrorGroupState::Unknown => "Unknown",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unknown_states_are_tolerated() {
+        let state: ErrorGroupState = serde_json::from_str(r#""Archived""#).unwrap();
+        assert_eq!(state, ErrorGroupState::Unknown);
+    }
+
+    #[test]
+    fn display_names_match_the_json_names() {
+        for state in [
+            ErrorGroupState::Open,
+            ErrorGroupState::Reopened,
+            ErrorGroupState::Closed,
+            ErrorGroupState::Ignored,
+            ErrorGroupState::Unknown,
+        ] {
+            let json = serde_json::to_string(&state).unwrap();
+            assert_eq!(json, format!("\"{}\"", state));
+        }
+    }
+}
@@ -5,14 +5,50 @@ pub mod crash_list;
 pub use self::crash_list::CrashList;
 
 mod version_list;
-pub use self::version_list::VersionList;
+pub use self::version_list::{VersionList, VersionOrdering};
 
 mod version;
 pub use self::version::Version;
 
+mod semantic_version;
+pub use self::semantic_version::SemanticVersion;
+
 mod report;
 pub use self::report::Report;
 
+mod report_document;
+pub use self::report_document::{
+    ReportDocument, ReportFilters, ReportThresholds, ReportedBuild, ReportedCrash,
+    ReportedCrashDiff, ReportedDiff, ReportedFrame, ReportedOperatingSystem, ReportedVersion,
+    REPORT_SCHEMA_VERSION,
+};
+
+mod build_count;
+pub use self::build_count::BuildCount;
+
+mod time_window;
+pub use self::time_window::TimeWindow;
+
+mod version_count;
+pub use self::version_count::VersionCount;
+
+mod crash_free_statistics;
+pub use self::crash_free_statistics::{
+    CrashCount, CrashFreeDevices, CrashFreeStatistics, SessionCount,
+};
+
+mod error_group_state;
+pub use self::error_group_state::ErrorGroupState;
+
 mod error_group_details;
 pub use self::error_group_details::ErrorGroup;
 pub use self::error_group_details::OperatingSystemCount;
+
+mod stacktrace;
+pub use self::stacktrace::{Exception, StackFrame, StackTrace, Thread};
+
+mod version_diff;
+pub use self::version_diff::{CrashChange, CrashDiff, VersionDiff};
+
+pub mod trend;
+pub use self::trend::{ErrorGroupTrend, Trend, TrendPoint};
@@ -1,10 +1,29 @@
+use crate::model::BuildCount;
+use crate::model::Crash;
+use crate::model::CrashFreeStatistics;
 use crate::model::CrashList;
 use crate::model::OperatingSystemCount;
+use crate::model::StackFrame;
+use crate::model::VersionCount;
+use crate::model::VersionDiff;
 use std::collections::HashMap;
 
 pub struct Report {
     pub version: String,
     pub crash_list: CrashList,
+
+    /// The versions combined into this report, most recent first. Empty if the report covers a
+    /// single version only.
+    pub versions: Vec<String>,
+
+    /// The share of users and sessions without crashes, if AppCenter provides it.
+    pub statistics: Option<CrashFreeStatistics>,
+
+    /// The build the crashes are restricted to, if any.
+    pub build: Option<String>,
+
+    /// The comparison with a base version, if requested.
+    pub diff: Option<VersionDiff>,
 }
 
 impl Report {
@@ -12,6 +31,102 @@ impl Report {
         Report {
             version,
             crash_list,
+            versions: Vec::new(),
+            statistics: None,
+            build: None,
+            diff: None,
+        }
+    }
+
+    /// Combines the reports of several versions, most recent first, into one report of the most
+    /// recent version. Crashes are merged by their error group id and sorted by their summed up
+    /// count, each listing the versions it occurred in. Devices affected in several versions are
+    /// counted once per version. Returns `None` if there are no reports.
+    pub fn combined(reports: Vec<Report>) -> Option<Report> {
+        let versions: Vec<String> = reports
+            .iter()
+            .map(|report| report.version.clone())
+            .collect();
+        let mut crashes: Vec<Crash> = Vec::new();
+
+        for report in reports {
+            for crash in report.crash_list.crashes {
+                let version_count = VersionCount {
+                    version: report.version.clone(),
+                    count: crash.count.unwrap_or(0),
+                    device_count: crash.device_count.unwrap_or(0),
+                };
+                let combined_crash = crashes.iter_mut().find(|combined| {
+                    combined.error_group_id.is_some()
+                        && combined.error_group_id == crash.error_group_id
+                });
+                match combined_crash {
+                    Some(combined) => {
+                        combined.count = Some(combined.count.unwrap_or(0) + version_count.count);
+                        combined.device_count =
+                            Some(combined.device_count.unwrap_or(0) + version_count.device_count);
+                        // ISO 8601 timestamps order lexicographically
+                        if let Some(first_occurrence) = crash.first_occurrence {
+                            if combined
+                                .first_occurrence
+                                .as_ref()
+                                .is_none_or(|combined| &first_occurrence < combined)
+                            {
+                                combined.first_occurrence = Some(first_occurrence);
+                            }
+                        }
+                        if let Some(last_occurrence) = crash.last_occurrence {
+                            if combined
+                                .last_occurrence
+                                .as_ref()
+                                .is_none_or(|combined| &last_occurrence > combined)
+                            {
+                                combined.last_occurrence = Some(last_occurrence);
+                            }
+                        }
+                        combined.reopened |= crash.reopened;
+                        combined
+                            .versions
+                            .get_or_insert_with(Vec::new)
+                            .push(version_count);
+                    }
+                    None => crashes.push(Crash {
+                        versions: Some(vec![version_count]),
+                        ..crash
+                    }),
+                }
+            }
+        }
+        crashes.sort_by_key(|crash| std::cmp::Reverse(crash.count.unwrap_or(0)));
+
+        let mut report = Report::new(
+            versions.first()?.clone(),
+            CrashList {
+                crashes,
+                next_link: None,
+            },
+        );
+        report.versions = versions;
+        Some(report)
+    }
+
+    pub fn assign_build_counts(&mut self, build_counts: HashMap<String, Vec<BuildCount>>) {
+        for crash in &mut self.crash_list.crashes {
+            if let Some(group_id) = &crash.error_group_id {
+                if let Some(builds) = build_counts.get(group_id) {
+                    crash.builds = Some(builds.to_vec());
+                }
+            }
+        }
+    }
+
+    pub fn assign_stacktraces(&mut self, stacktraces: HashMap<String, Vec<StackFrame>>) {
+        for crash in &mut self.crash_list.crashes {
+            if let Some(group_id) = &crash.error_group_id {
+                if let Some(frames) = stacktraces.get(group_id) {
+                    crash.stacktrace = Some(frames.to_vec());
+                }
+            }
         }
     }
 
@@ -28,3 +143,58 @@ impl Report {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(version: &str, crashes: Vec<(&str, u64, &str)>) -> Report {
+        let crashes = crashes
+            .into_iter()
+            .map(|(error_group_id, count, first_occurrence)| Crash {
+                error_group_id: Some(error_group_id.to_string()),
+                count: Some(count),
+                device_count: Some(1),
+                first_occurrence: Some(first_occurrence.to_string()),
+                ..Crash::default()
+            })
+            .collect();
+        Report::new(
+            version.to_string(),
+            CrashList {
+                crashes,
+                next_link: None,
+            },
+        )
+    }
+
+    #[test]
+    fn combined_reports_sum_up_counts_per_error_group() {
+        let reports = vec![
+            report("1.1", vec![("a", 2, "2019-11-18"), ("b", 5, "2019-11-18")]),
+            report("1.0", vec![("a", 4, "2019-11-10")]),
+        ];
+
+        let report = Report::combined(reports).unwrap();
+
+        assert_eq!(report.version, "1.1");
+        assert_eq!(report.versions, vec!["1.1", "1.0"]);
+        let a = &report.crash_list.crashes[0];
+        assert_eq!(a.error_group_id.as_deref(), Some("a"));
+        assert_eq!(a.count, Some(6));
+        assert_eq!(a.device_count, Some(2));
+        assert_eq!(a.first_occurrence.as_deref(), Some("2019-11-10"));
+        let versions: Vec<(&str, u64)> = a
+            .versions
+            .iter()
+            .flatten()
+            .map(|version_count| (version_count.version.as_str(), version_count.count))
+            .collect();
+        assert_eq!(versions, vec![("1.1", 2), ("1.0", 4)]);
+    }
+
+    #[test]
+    fn no_reports_are_combined_into_nothing() {
+        assert!(Report::combined(Vec::new()).is_none());
+    }
+}
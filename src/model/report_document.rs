@@ -0,0 +1,324 @@
+use crate::model::{
+    Crash, CrashChange, CrashDiff, CrashFreeStatistics, ErrorGroupState, StackFrame, TimeWindow,
+    VersionDiff,
+};
+use serde::Serialize;
+
+/// The version of the json schema of `ReportDocument`. Raised whenever a field is renamed or
+/// removed, while added fields keep the version.
+pub const REPORT_SCHEMA_VERSION: u32 = 1;
+
+/// A report as written in the json format, meant to be processed by other tools.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportDocument {
+    /// Always `REPORT_SCHEMA_VERSION`, so tools can detect incompatible changes.
+    pub schema_version: u32,
+
+    pub organization: String,
+
+    pub application: String,
+
+    /// The reported version, or the most recent one if several versions are combined.
+    pub version: String,
+
+    /// The combined versions, most recent first. Empty if a single version is reported.
+    pub versions: Vec<String>,
+
+    /// The build the crashes are restricted to, if any.
+    pub build: Option<String>,
+
+    /// The window the crashes occurred in. AppCenter's default period if none is given.
+    pub time_window: Option<TimeWindow>,
+
+    pub filters: ReportFilters,
+
+    pub thresholds: ReportThresholds,
+
+    /// The share of users and sessions without crashes, if AppCenter provides it.
+    pub statistics: Option<CrashFreeStatistics>,
+
+    /// The reported crashes, most frequent first.
+    pub crashes: Vec<ReportedCrash>,
+
+    /// The comparison with the base version, if requested.
+    pub diff: Option<ReportedDiff>,
+}
+
+/// The filters which removed crashes from the report.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportFilters {
+    /// Whether error groups classified as errors are omitted.
+    pub omit_errors: bool,
+
+    /// The states of the reported error groups. All states are reported if empty.
+    pub states: Vec<ErrorGroupState>,
+
+    /// The maximum amount of error groups fetched, if limited.
+    pub error_group_limit: Option<usize>,
+
+    /// Whether crashes occurring less often than the arithmetic mean are omitted.
+    pub arithmetic_mean: bool,
+}
+
+/// The thresholds the report was checked against.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportThresholds {
+    /// The amount of crashes representing 100%.
+    pub threshold: Option<u64>,
+
+    /// The arithmetic mean of all crashes, if used as threshold.
+    pub arithmetic_mean: Option<u64>,
+
+    pub min_crash_free_users: Option<f64>,
+
+    pub min_crash_free_sessions: Option<f64>,
+
+    /// The amount of crash-free rates below their minimum.
+    pub crash_free_rates_below_minimum: usize,
+}
+
+/// A crash of the report, along with how it relates to the thresholds.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ReportedCrash {
+    pub error_group_id: Option<String>,
+
+    pub count: u64,
+
+    pub device_count: u64,
+
+    pub app_version: Option<String>,
+
+    pub app_build: Option<String>,
+
+    pub first_occurrence: Option<String>,
+
+    pub last_occurrence: Option<String>,
+
+    /// The state of the error group in AppCenter.
+    pub state: ErrorGroupState,
+
+    /// Whether the error group is closed in AppCenter, but crashed again within the reported
+    /// period.
+    pub reopened: bool,
+
+    pub exception_type: Option<String>,
+
+    pub exception_message: Option<String>,
+
+    pub exception_file: Option<String>,
+
+    pub exception_class_name: Option<String>,
+
+    pub exception_method: Option<String>,
+
+    /// Whether the exception was thrown in the code of the app.
+    pub exception_app_code: Option<bool>,
+
+    pub code_raw: Option<String>,
+
+    /// The frame the exception was thrown in, if AppCenter knows it.
+    pub reason_frame: Option<ReportedFrame>,
+
+    /// The topmost frames of the stack trace. Empty unless requested.
+    pub stacktrace: Vec<ReportedFrame>,
+
+    /// The affected operating systems. Empty unless requested.
+    pub operating_systems: Vec<ReportedOperatingSystem>,
+
+    /// The occurrences per build. Empty unless a breakdown per build was requested.
+    pub builds: Vec<ReportedBuild>,
+
+    /// The occurrences per version. Empty unless several versions are combined.
+    pub versions: Vec<ReportedVersion>,
+
+    /// The share of the threshold reached, e.g. `150.0` for 150%. Only set with a threshold.
+    pub threshold_percentage: Option<f64>,
+
+    pub threshold_exceeded: bool,
+}
+
+/// A frame of a stack trace.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportedFrame {
+    pub class_name: Option<String>,
+
+    pub method: Option<String>,
+
+    pub file: Option<String>,
+
+    pub line: Option<u64>,
+
+    /// Whether the frame belongs to the code of the app rather than to a system framework.
+    pub app_code: Option<bool>,
+
+    /// The frame as shown by AppCenter, e.g. `-[XNGProfileViewController viewDidLoad]
+    /// (XNGProfileViewController.m:57)`.
+    pub code_formatted: Option<String>,
+}
+
+/// The occurrences of a crash on an operating system.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportedOperatingSystem {
+    pub name: String,
+
+    pub count: u64,
+}
+
+/// The occurrences of a crash in a build.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportedBuild {
+    pub build: String,
+
+    pub count: u64,
+
+    pub device_count: u64,
+}
+
+/// The occurrences of a crash in a version.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportedVersion {
+    pub version: String,
+
+    pub count: u64,
+
+    pub device_count: u64,
+}
+
+/// The comparison with a base version.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportedDiff {
+    pub base_version: String,
+
+    pub version: String,
+
+    /// The percentage a count has to change by to count as regression or improvement.
+    pub regression_threshold: f64,
+
+    pub new: Vec<ReportedCrashDiff>,
+
+    pub regressed: Vec<ReportedCrashDiff>,
+
+    pub improved: Vec<ReportedCrashDiff>,
+
+    pub resolved: Vec<ReportedCrashDiff>,
+
+    pub unchanged: Vec<ReportedCrashDiff>,
+}
+
+/// How a crash changed compared to the base version.
+#[derive(Debug, Clone, Serialize)]
+pub struct ReportedCrashDiff {
+    pub change: CrashChange,
+
+    /// The crash of the compared version, or of the base version if it was resolved.
+    pub crash: ReportedCrash,
+
+    pub base_count: Option<u64>,
+
+    pub count: Option<u64>,
+
+    /// The relative change of the count in percent. Only set if the crash occurs in both versions.
+    pub change_percentage: Option<f64>,
+}
+
+impl From<&Crash> for ReportedCrash {
+    /// Maps a crash as returned by AppCenter. The threshold fields are left for the reporter.
+    fn from(crash: &Crash) -> ReportedCrash {
+        ReportedCrash {
+            error_group_id: crash.error_group_id.clone(),
+            count: crash.count.unwrap_or(0),
+            device_count: crash.device_count.unwrap_or(0),
+            app_version: crash.app_version.clone(),
+            app_build: crash.app_build.clone(),
+            first_occurrence: crash.first_occurrence.clone(),
+            last_occurrence: crash.last_occurrence.clone(),
+            state: crash.state(),
+            reopened: crash.reopened,
+            exception_type: crash.exception_type.clone(),
+            exception_message: crash.exception_message.clone(),
+            exception_file: crash.exception_file.clone(),
+            exception_class_name: crash.exception_classname.clone(),
+            exception_method: crash.exception_method.clone(),
+            exception_app_code: crash.exception_app_code,
+            code_raw: crash.code_raw.clone(),
+            reason_frame: crash.reason_frame.as_ref().map(ReportedFrame::from),
+            stacktrace: crash
+                .stacktrace
+                .iter()
+                .flatten()
+                .map(ReportedFrame::from)
+                .collect(),
+            operating_systems: crash
+                .operating_systems
+                .iter()
+                .flatten()
+                .map(|os| ReportedOperatingSystem {
+                    name: os.name.clone(),
+                    count: os.count,
+                })
+                .collect(),
+            builds: crash
+                .builds
+                .iter()
+                .flatten()
+                .map(|build| ReportedBuild {
+                    build: build.build.clone(),
+                    count: build.count,
+                    device_count: build.device_count,
+                })
+                .collect(),
+            versions: crash
+                .versions
+                .iter()
+                .flatten()
+                .map(|version| ReportedVersion {
+                    version: version.version.clone(),
+                    count: version.count,
+                    device_count: version.device_count,
+                })
+                .collect(),
+            threshold_percentage: None,
+            threshold_exceeded: false,
+        }
+    }
+}
+
+impl From<&StackFrame> for ReportedFrame {
+    fn from(frame: &StackFrame) -> ReportedFrame {
+        ReportedFrame {
+            class_name: frame.class_name.clone(),
+            method: frame.method.clone(),
+            file: frame.file.clone(),
+            line: frame.line,
+            app_code: frame.app_code,
+            code_formatted: frame.code_formatted.clone(),
+        }
+    }
+}
+
+impl From<&VersionDiff> for ReportedDiff {
+    fn from(diff: &VersionDiff) -> ReportedDiff {
+        let changes = |crash_diffs: &[CrashDiff]| {
+            crash_diffs
+                .iter()
+                .map(|crash_diff| ReportedCrashDiff {
+                    change: crash_diff.change,
+                    crash: ReportedCrash::from(&crash_diff.crash),
+                    base_count: crash_diff.base_count,
+                    count: crash_diff.count,
+                    change_percentage: crash_diff.change_percentage,
+                })
+                .collect()
+        };
+        ReportedDiff {
+            base_version: diff.base_version.clone(),
+            version: diff.version.clone(),
+            regression_threshold: diff.regression_threshold,
+            new: changes(&diff.new),
+            regressed: changes(&diff.regressed),
+            improved: changes(&diff.improved),
+            resolved: changes(&diff.resolved),
+            unchanged: changes(&diff.unchanged),
+        }
+    }
+}
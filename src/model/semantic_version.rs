@@ -0,0 +1,134 @@
+use std::cmp::Ordering;
+
+/// A version number parsed for ordering, e.g. `7.41.3`, `7.41.3.1` or `7.42.0-beta.2`.
+///
+/// Unlike strict semantic versioning any amount of numeric parts is accepted. Missing parts count
+/// as zero, so `7.41` equals `7.41.0`. Pre-releases order before their release and build metadata
+/// after a `+` is ignored.
+///
+/// ```
+/// use recrep::model::SemanticVersion;
+///
+/// let parse = |version| SemanticVersion::parse(version).unwrap();
+///
+/// assert!(parse("7.40.1") < parse("7.41.0"));
+/// assert!(parse("7.41.0.1") > parse("7.41"));
+/// assert!(parse("7.42.0-beta.2") < parse("7.42.0"));
+/// assert!(parse("7.42.0-beta.2") < parse("7.42.0-beta.10"));
+/// assert!(SemanticVersion::parse("nightly").is_none());
+/// ```
+#[derive(Debug, Clone)]
+pub struct SemanticVersion {
+    numbers: Vec<u64>,
+
+    pre_release: Option<String>,
+}
+
+impl SemanticVersion {
+    /// Parses the version, tolerating a leading `v`. Returns `None` if the version does not start
+    /// with dot separated numbers.
+    pub fn parse(version: &str) -> Option<SemanticVersion> {
+        let version = version.trim();
+        let version = version.strip_prefix('v').unwrap_or(version);
+        let version = version.split('+').next().unwrap_or(version);
+        let (numbers, pre_release) = match version.split_once('-') {
+            Some((numbers, pre_release)) => (numbers, Some(pre_release.to_string())),
+            None => (version, None),
+        };
+
+        let numbers = numbers
+            .split('.')
+            .map(|number| number.parse::<u64>().ok())
+            .collect::<Option<Vec<u64>>>()?;
+
+        Some(SemanticVersion {
+            numbers,
+            pre_release,
+        })
+    }
+
+    fn number(&self, index: usize) -> u64 {
+        self.numbers.get(index).copied().unwrap_or(0)
+    }
+}
+
+/// Compares two pre-release identifiers like `beta.2` part by part. Numeric parts are compared as
+/// numbers and order before alphanumeric parts.
+fn compare_pre_releases(a: &str, b: &str) -> Ordering {
+    let mut a_parts = a.split('.');
+    let mut b_parts = b.split('.');
+    loop {
+        let ordering = match (a_parts.next(), b_parts.next()) {
+            (None, None) => return Ordering::Equal,
+            (None, Some(_)) => return Ordering::Less,
+            (Some(_), None) => return Ordering::Greater,
+            (Some(a), Some(b)) => match (a.parse::<u64>(), b.parse::<u64>()) {
+                (Ok(a), Ok(b)) => a.cmp(&b),
+                (Ok(_), Err(_)) => Ordering::Less,
+                (Err(_), Ok(_)) => Ordering::Greater,
+                (Err(_), Err(_)) => a.cmp(b),
+            },
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+impl Ord for SemanticVersion {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let parts = self.numbers.len().max(other.numbers.len());
+        (0..parts)
+            .map(|index| self.number(index).cmp(&other.number(index)))
+            .find(|ordering| ordering != &Ordering::Equal)
+            .unwrap_or_else(|| match (&self.pre_release, &other.pre_release) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(_), None) => Ordering::Less,
+                (Some(a), Some(b)) => compare_pre_releases(a, b),
+            })
+    }
+}
+
+impl PartialOrd for SemanticVersion {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for SemanticVersion {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SemanticVersion {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn missing_parts_count_as_zero() {
+        assert_eq!(
+            SemanticVersion::parse("7.41").unwrap(),
+            SemanticVersion::parse("7.41.0.0").unwrap()
+        );
+    }
+
+    #[test]
+    fn build_metadata_and_prefix_are_ignored() {
+        assert_eq!(
+            SemanticVersion::parse("v7.41.3+10167").unwrap(),
+            SemanticVersion::parse("7.41.3").unwrap()
+        );
+    }
+
+    #[test]
+    fn pre_releases_are_ordered_by_their_identifiers() {
+        let parse = |version| SemanticVersion::parse(version).unwrap();
+        assert!(parse("1.0.0-alpha") < parse("1.0.0-alpha.1"));
+        assert!(parse("1.0.0-alpha.1") < parse("1.0.0-alpha.beta"));
+        assert!(parse("1.0.0-beta") < parse("1.0.0-rc.1"));
+    }
+}
@@ -0,0 +1,162 @@
+use serde::{Deserialize, Serialize};
+
+/// The representative stack trace of an error group.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StackTrace {
+    pub title: Option<String>,
+
+    pub reason: Option<String>,
+
+    #[serde(default)]
+    pub threads: Vec<Thread>,
+
+    pub exception: Option<Exception>,
+}
+
+/// A thread running when the app crashed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Thread {
+    pub title: Option<String>,
+
+    #[serde(default)]
+    pub frames: Vec<StackFrame>,
+
+    pub exception: Option<Exception>,
+
+    /// Whether the thread caused the crash.
+    pub relevant: Option<bool>,
+}
+
+/// The exception which caused the crash.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Exception {
+    #[serde(rename = "type")]
+    pub exception_type: Option<String>,
+
+    pub reason: Option<String>,
+
+    #[serde(default)]
+    pub frames: Vec<StackFrame>,
+
+    pub relevant: Option<bool>,
+}
+
+/// A single frame of a stack trace.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StackFrame {
+    #[serde(rename = "className")]
+    pub class_name: Option<String>,
+
+    pub method: Option<String>,
+
+    pub file: Option<String>,
+
+    pub line: Option<u64>,
+
+    /// Whether the frame belongs to the code of the app rather than to a system framework.
+    #[serde(rename = "appCode")]
+    pub app_code: Option<bool>,
+
+    #[serde(rename = "frameworkName")]
+    pub framework_name: Option<String>,
+
+    /// The frame as shown by AppCenter, e.g. `-[XNGProfileViewController viewDidLoad]
+    /// (XNGProfileViewController.m:57)`.
+    #[serde(rename = "codeFormatted")]
+    pub code_formatted: Option<String>,
+
+    #[serde(rename = "codeRaw")]
+    pub code_raw: Option<String>,
+}
+
+impl StackTrace {
+    /// The frames leading to the crash: the ones of the exception if AppCenter knows them,
+    /// otherwise the ones of the thread which crashed.
+    pub fn frames(&self) -> &[StackFrame] {
+        if let Some(exception) = &self.exception {
+            if !exception.frames.is_empty() {
+                return &exception.frames;
+            }
+        }
+
+        let thread = self
+            .threads
+            .iter()
+            .find(|thread| thread.relevant == Some(true))
+            .or_else(|| self.threads.first());
+        match thread {
+            Some(thread) => match &thread.exception {
+                Some(exception) if !exception.frames.is_empty() => &exception.frames,
+                _ => &thread.frames,
+            },
+            None => &[],
+        }
+    }
+
+    /// The topmost `count` frames leading to the crash.
+    ///
+    /// ```
+    /// use recrep::json_parsing::crash_parsing;
+    /// use recrep::utils::TestHelper;
+    ///
+    /// let json = TestHelper::fixture("src/json_parsing/test_fixtures/stacktrace.json");
+    /// let stacktrace = crash_parsing::stacktrace_from_json(&json).unwrap();
+    ///
+    /// let frames = stacktrace.top_frames(3);
+    /// assert_eq!(frames.len(), 3);
+    /// assert_eq!(frames[2].app_code, Some(true));
+    /// ```
+    pub fn top_frames(&self, count: usize) -> Vec<StackFrame> {
+        self.frames().iter().take(count).cloned().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(method: &str) -> StackFrame {
+        StackFrame {
+            method: Some(method.to_string()),
+            ..StackFrame::default()
+        }
+    }
+
+    fn thread(relevant: bool, method: &str) -> Thread {
+        Thread {
+            title: None,
+            frames: vec![frame(method)],
+            exception: None,
+            relevant: Some(relevant),
+        }
+    }
+
+    #[test]
+    fn the_crashed_thread_is_used_without_exception_frames() {
+        let stacktrace = StackTrace {
+            title: None,
+            reason: None,
+            threads: vec![thread(false, "main"), thread(true, "worker")],
+            exception: Some(Exception {
+                exception_type: Some("SIGSEGV".to_string()),
+                reason: None,
+                frames: Vec::new(),
+                relevant: None,
+            }),
+        };
+
+        assert_eq!(stacktrace.frames()[0].method.as_deref(), Some("worker"));
+    }
+
+    #[test]
+    fn the_first_thread_is_used_if_none_is_marked_as_relevant() {
+        let stacktrace = StackTrace {
+            title: None,
+            reason: None,
+            threads: vec![thread(false, "main"), thread(false, "worker")],
+            exception: None,
+        };
+
+        assert_eq!(stacktrace.frames()[0].method.as_deref(), Some("main"));
+    }
+}
@@ -0,0 +1,65 @@
+use chrono::{DateTime, SecondsFormat, TimeDelta, Utc};
+use serde::{Serialize, Serializer};
+
+/// Restricts error groups to the crashes which occurred within a period of time.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct TimeWindow {
+    #[serde(serialize_with = "serialize_time")]
+    pub start: DateTime<Utc>,
+
+    #[serde(serialize_with = "serialize_time")]
+    pub end: DateTime<Utc>,
+}
+
+impl TimeWindow {
+    pub fn new(start: DateTime<Utc>, end: DateTime<Utc>) -> TimeWindow {
+        TimeWindow { start, end }
+    }
+
+    /// The window ending now, e.g. the last 24 hours.
+    pub fn last(duration: TimeDelta) -> TimeWindow {
+        let end = Utc::now();
+        TimeWindow::new(end - duration, end)
+    }
+
+    /// Whether the point in time lies within the window, including its start and end.
+    pub fn contains(&self, time: DateTime<Utc>) -> bool {
+        self.start <= time && time <= self.end
+    }
+
+    /// The query parameters of the AppCenter errorGroups endpoint.
+    ///
+    /// ```
+    /// use recrep::model::TimeWindow;
+    ///
+    /// let window = TimeWindow::new(
+    ///     "2019-05-15T00:00:00Z".parse().unwrap(),
+    ///     "2019-05-16T12:30:00Z".parse().unwrap(),
+    /// );
+    /// assert_eq!(window.query(), "start=2019-05-15T00:00:00Z&end=2019-05-16T12:30:00Z");
+    /// ```
+    pub fn query(&self) -> String {
+        format!(
+            "start={}&end={}",
+            format_time(self.start),
+            format_time(self.end)
+        )
+    }
+
+    /// Identifies the window in the key of a stored response.
+    pub fn request_parameter(&self) -> String {
+        format!(
+            "{}-{}",
+            self.start.format("%Y%m%dT%H%M%SZ"),
+            self.end.format("%Y%m%dT%H%M%SZ")
+        )
+    }
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn serialize_time<S: Serializer>(time: &DateTime<Utc>, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format_time(*time))
+}
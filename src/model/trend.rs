@@ -0,0 +1,184 @@
+use crate::model::{Crash, Report, VersionCount};
+use serde::Serialize;
+
+/// The bars of a sparkline, from the lowest to the highest value.
+static SPARKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Marks versions without any crash of an error group in a sparkline.
+static NO_SPARK: char = '·';
+
+/// The occurrences of an error group in a single version of the trend.
+pub type TrendPoint = VersionCount;
+
+/// The occurrences of an error group across several versions.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorGroupTrend {
+    pub error_group_id: String,
+
+    /// The crash as reported in the most recent version it occurred in.
+    pub crash: Crash,
+
+    /// One point per version, from the oldest to the most recent version.
+    pub points: Vec<TrendPoint>,
+
+    /// The counts of all points as text, e.g. `▁▃·█`.
+    pub sparkline: String,
+}
+
+/// The crash trend across several versions.
+#[derive(Debug, Clone, Serialize)]
+pub struct Trend {
+    /// The versions from the oldest to the most recent one.
+    pub versions: Vec<String>,
+
+    /// The error groups, ordered by their count in the most recent version.
+    pub error_groups: Vec<ErrorGroupTrend>,
+}
+
+impl Trend {
+    /// Builds the trend from the reports of several versions, ordered from the oldest to the most
+    /// recent version. Crashes without an error group id can't be matched and are skipped.
+    pub fn from_reports(reports: &[Report]) -> Trend {
+        let versions: Vec<String> = reports
+            .iter()
+            .map(|report| report.version.clone())
+            .collect();
+        let mut error_groups: Vec<ErrorGroupTrend> = Vec::new();
+
+        for (index, report) in reports.iter().enumerate() {
+            for crash in &report.crash_list.crashes {
+                let error_group_id = match &crash.error_group_id {
+                    Some(error_group_id) => error_group_id,
+                    None => continue,
+                };
+                let position = error_groups
+                    .iter()
+                    .position(|trend| &trend.error_group_id == error_group_id);
+                let trend = match position {
+                    Some(position) => &mut error_groups[position],
+                    None => {
+                        error_groups.push(ErrorGroupTrend {
+                            error_group_id: error_group_id.clone(),
+                            crash: crash.clone(),
+                            points: versions
+                                .iter()
+                                .map(|version| TrendPoint {
+                                    version: version.clone(),
+                                    count: 0,
+                                    device_count: 0,
+                                })
+                                .collect(),
+                            sparkline: String::new(),
+                        });
+                        error_groups.last_mut().unwrap()
+                    }
+                };
+                trend.crash = crash.clone();
+                trend.points[index].count += crash.count.unwrap_or(0);
+                trend.points[index].device_count += crash.device_count.unwrap_or(0);
+            }
+        }
+
+        for trend in error_groups.iter_mut() {
+            let counts: Vec<u64> = trend.points.iter().map(|point| point.count).collect();
+            trend.sparkline = sparkline(&counts);
+        }
+        error_groups.sort_by(|a, b| {
+            let latest_count = |trend: &ErrorGroupTrend| trend.points.last().map(|p| p.count);
+            let total_count = |trend: &ErrorGroupTrend| -> u64 {
+                trend.points.iter().map(|point| point.count).sum()
+            };
+            latest_count(b)
+                .cmp(&latest_count(a))
+                .then(total_count(b).cmp(&total_count(a)))
+        });
+
+        Trend {
+            versions,
+            error_groups,
+        }
+    }
+
+    /// Drops all error groups the predicate rejects, e.g. to omit errors.
+    pub fn retain(&mut self, predicate: impl Fn(&Crash) -> bool) {
+        self.error_groups.retain(|trend| predicate(&trend.crash));
+    }
+}
+
+/// Renders the values as bars scaled to the highest value.
+///
+/// ```
+/// use recrep::model::trend::sparkline;
+///
+/// assert_eq!(sparkline(&[1, 4, 0, 8]), "▂▅·█");
+/// ```
+pub fn sparkline(values: &[u64]) -> String {
+    let max = values.iter().copied().max().unwrap_or(0);
+    values
+        .iter()
+        .map(|&value| {
+            if value == 0 {
+                NO_SPARK
+            } else {
+                let level = value as f64 / max as f64 * (SPARKS.len() - 1) as f64;
+                SPARKS[level.round() as usize]
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::CrashList;
+
+    fn report(version: &str, crashes: Vec<(&str, u64)>) -> Report {
+        let crashes = crashes
+            .into_iter()
+            .map(|(error_group_id, count)| Crash {
+                error_group_id: Some(error_group_id.to_string()),
+                count: Some(count),
+                device_count: Some(count / 2),
+                ..Crash::default()
+            })
+            .collect();
+        Report::new(
+            version.to_string(),
+            CrashList {
+                crashes,
+                next_link: None,
+            },
+        )
+    }
+
+    #[test]
+    fn error_groups_are_tracked_across_versions() {
+        let reports = vec![
+            report("1.0", vec![("a", 10), ("b", 2)]),
+            report("1.1", vec![("a", 4)]),
+            report("1.2", vec![("b", 6), ("a", 2), ("c", 1)]),
+        ];
+
+        let trend = Trend::from_reports(&reports);
+
+        assert_eq!(trend.versions, vec!["1.0", "1.1", "1.2"]);
+        let ids: Vec<&str> = trend
+            .error_groups
+            .iter()
+            .map(|trend| trend.error_group_id.as_str())
+            .collect();
+        assert_eq!(ids, vec!["b", "a", "c"]);
+
+        let b = &trend.error_groups[0];
+        let counts: Vec<u64> = b.points.iter().map(|point| point.count).collect();
+        assert_eq!(counts, vec![2, 0, 6]);
+        assert_eq!(b.points[2].device_count, 3);
+        assert_eq!(b.sparkline, "▃·█");
+    }
+
+    #[test]
+    fn empty_sparkline() {
+        assert_eq!(sparkline(&[]), "");
+        assert_eq!(sparkline(&[0, 0]), "··");
+    }
+}
@@ -1,14 +1,27 @@
+use crate::model::SemanticVersion;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Version {
     pub short_version: String,
 
-    pub uploaded_at: String,
+    /// The build number, e.g. `10167`. Several builds may share the same short version.
+    #[serde(rename = "version")]
+    pub build: Option<String>,
+
+    pub uploaded_at: DateTime<Utc>,
 
     pub distribution_groups: Option<Vec<DistributionGroup>>,
 }
 
+impl Version {
+    /// The short version parsed for ordering, if it is a version number.
+    pub fn semantic_version(&self) -> Option<SemanticVersion> {
+        SemanticVersion::parse(&self.short_version)
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DistributionGroup {
     pub id: String,
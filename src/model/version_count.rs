@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+
+/// The occurrences of an error group in a single version.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionCount {
+    pub version: String,
+
+    pub count: u64,
+
+    #[serde(rename = "deviceCount")]
+    pub device_count: u64,
+}
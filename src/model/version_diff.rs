@@ -0,0 +1,261 @@
+use crate::model::{Crash, CrashList};
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// How an error group changed from one version to another.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CrashChange {
+    /// The error group did not occur in the base version.
+    New,
+
+    /// The error group occurs in the base version only.
+    Resolved,
+
+    /// The count increased by more than the regression threshold.
+    Regressed,
+
+    /// The count decreased by more than the regression threshold.
+    Improved,
+
+    /// The count changed within the regression threshold.
+    Unchanged,
+}
+
+/// The change of a single error group between two versions.
+#[derive(Debug, Clone, Serialize)]
+pub struct CrashDiff {
+    pub change: CrashChange,
+
+    /// The crash of the compared version, or of the base version if it was resolved.
+    pub crash: Crash,
+
+    pub base_count: Option<u64>,
+
+    pub count: Option<u64>,
+
+    /// The relative change of the count in percent. Only set if the error group occurs in both
+    /// versions.
+    pub change_percentage: Option<f64>,
+}
+
+/// Compares the error groups of a version with the ones of a base version, e.g. the previous
+/// release.
+#[derive(Debug, Clone, Serialize)]
+pub struct VersionDiff {
+    pub base_version: String,
+
+    pub version: String,
+
+    /// The percentage a count has to change by to count as regression or improvement.
+    pub regression_threshold: f64,
+
+    pub new: Vec<CrashDiff>,
+
+    pub regressed: Vec<CrashDiff>,
+
+    pub improved: Vec<CrashDiff>,
+
+    pub resolved: Vec<CrashDiff>,
+
+    pub unchanged: Vec<CrashDiff>,
+}
+
+impl VersionDiff {
+    /// Classifies every error group of both crash lists by its `errorGroupId`. Crashes without an
+    /// error group id can't be matched and are skipped, missing counts count as 0.
+    ///
+    /// Both crash lists must be complete: an error group cut off by a limit would show up as new
+    /// or resolved.
+    pub fn between(
+        base_version: &str,
+        base: &CrashList,
+        version: &str,
+        crash_list: &CrashList,
+        regression_threshold: f64,
+    ) -> VersionDiff {
+        let mut diff = VersionDiff {
+            base_version: base_version.to_string(),
+            version: version.to_string(),
+            regression_threshold,
+            new: Vec::new(),
+            regressed: Vec::new(),
+            improved: Vec::new(),
+            resolved: Vec::new(),
+            unchanged: Vec::new(),
+        };
+
+        let base_crashes: HashMap<&String, &Crash> = base
+            .crashes
+            .iter()
+            .filter_map(|crash| crash.error_group_id.as_ref().map(|id| (id, crash)))
+            .collect();
+
+        for crash in &crash_list.crashes {
+            let error_group_id = match &crash.error_group_id {
+                Some(error_group_id) => error_group_id,
+                None => continue,
+            };
+            let base_count = base_crashes.get(error_group_id).map(|base| base.count);
+            diff.push(
+                crash,
+                base_count.map(|count| count.unwrap_or(0)),
+                Some(crash.count.unwrap_or(0)),
+            );
+        }
+
+        let error_group_ids: Vec<&String> = crash_list
+            .crashes
+            .iter()
+            .filter_map(|crash| crash.error_group_id.as_ref())
+            .collect();
+        for crash in &base.crashes {
+            if let Some(error_group_id) = &crash.error_group_id {
+                if !error_group_ids.contains(&error_group_id) {
+                    diff.push(crash, Some(crash.count.unwrap_or(0)), None);
+                }
+            }
+        }
+
+        diff
+    }
+
+    fn push(&mut self, crash: &Crash, base_count: Option<u64>, count: Option<u64>) {
+        let (change, change_percentage) = match (base_count, count) {
+            (None, _) => (CrashChange::New, None),
+            (Some(_), None) => (CrashChange::Resolved, None),
+            (Some(0), Some(0)) => (CrashChange::Unchanged, Some(0.0)),
+            (Some(0), Some(_)) => (CrashChange::Regressed, None),
+            (Some(base_count), Some(count)) => {
+                let percentage = (count as f64 - base_count as f64) / base_count as f64 * 100.0;
+                let change = if percentage > self.regression_threshold {
+                    CrashChange::Regressed
+                } else if percentage < -self.regression_threshold {
+                    CrashChange::Improved
+                } else {
+                    CrashChange::Unchanged
+                };
+                (change, Some(percentage))
+            }
+        };
+
+        let crash_diff = CrashDiff {
+            change,
+            crash: crash.clone(),
+            base_count,
+            count,
+            change_percentage,
+        };
+        match change {
+            CrashChange::New => self.new.push(crash_diff),
+            CrashChange::Resolved => self.resolved.push(crash_diff),
+            CrashChange::Regressed => self.regressed.push(crash_diff),
+            CrashChange::Improved => self.improved.push(crash_diff),
+            CrashChange::Unchanged => self.unchanged.push(crash_diff),
+        }
+    }
+
+    /// Drops all error groups the predicate rejects, e.g. to omit errors.
+    pub fn retain(&mut self, predicate: impl Fn(&Crash) -> bool) {
+        for crash_diffs in [
+            &mut self.new,
+            &mut self.regressed,
+            &mut self.improved,
+            &mut self.resolved,
+            &mut self.unchanged,
+        ] {
+            crash_diffs.retain(|crash_diff| predicate(&crash_diff.crash));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn crash(error_group_id: &str, count: u64) -> Crash {
+        Crash {
+            error_group_id: Some(error_group_id.to_string()),
+            count: Some(count),
+            ..Crash::default()
+        }
+    }
+
+    fn crash_list(crashes: Vec<Crash>) -> CrashList {
+        CrashList {
+            crashes,
+            next_link: None,
+        }
+    }
+
+    fn ids(crash_diffs: &[CrashDiff]) -> Vec<&str> {
+        crash_diffs
+            .iter()
+            .map(|crash_diff| crash_diff.crash.error_group_id.as_deref().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn error_groups_are_classified() {
+        let base = crash_list(vec![
+            crash("regressed", 10),
+            crash("improved", 10),
+            crash("unchanged", 10),
+            crash("resolved", 10),
+        ]);
+        let version = crash_list(vec![
+            crash("regressed", 12),
+            crash("improved", 8),
+            crash("unchanged", 11),
+            crash("new", 5),
+        ]);
+
+        let diff = VersionDiff::between("1.0", &base, "1.1", &version, 10.0);
+
+        assert_eq!(ids(&diff.new), vec!["new"]);
+        assert_eq!(ids(&diff.regressed), vec!["regressed"]);
+        assert_eq!(ids(&diff.improved), vec!["improved"]);
+        assert_eq!(ids(&diff.unchanged), vec!["unchanged"]);
+        assert_eq!(ids(&diff.resolved), vec!["resolved"]);
+        assert_eq!(diff.regressed[0].change_percentage, Some(20.0));
+        assert_eq!(diff.resolved[0].count, None);
+        assert_eq!(diff.new[0].base_count, None);
+    }
+
+    #[test]
+    fn changes_within_the_threshold_are_unchanged() {
+        let base = crash_list(vec![crash("a", 100)]);
+        let version = crash_list(vec![crash("a", 150)]);
+
+        let diff = VersionDiff::between("1.0", &base, "1.1", &version, 50.0);
+
+        assert_eq!(ids(&diff.unchanged), vec!["a"]);
+    }
+
+    #[test]
+    fn missing_counts_are_not_resolved() {
+        let without_count = Crash {
+            count: None,
+            ..crash("a", 0)
+        };
+        let base = crash_list(vec![without_count.clone()]);
+        let version = crash_list(vec![without_count]);
+
+        let diff = VersionDiff::between("1.0", &base, "1.1", &version, 10.0);
+
+        assert!(diff.resolved.is_empty());
+        assert_eq!(ids(&diff.unchanged), vec!["a"]);
+    }
+
+    #[test]
+    fn retain_filters_all_changes() {
+        let base = crash_list(vec![crash("1u", 1), crash("2", 1)]);
+        let version = crash_list(vec![crash("3u", 1), crash("4", 1)]);
+
+        let mut diff = VersionDiff::between("1.0", &base, "1.1", &version, 10.0);
+        diff.retain(|crash| crash.is_crash());
+
+        assert_eq!(ids(&diff.new), vec!["3u"]);
+        assert_eq!(ids(&diff.resolved), vec!["1u"]);
+    }
+}
@@ -1,28 +1,123 @@
+use crate::error::{Error, Result};
 use crate::model::version::{DistributionGroup, Version};
+use chrono::{DateTime, Utc};
+use std::cmp::Reverse;
+use std::str::FromStr;
+
+/// How versions are ordered to find the latest one.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum VersionOrdering {
+    /// The most recently uploaded version is the latest.
+    #[default]
+    UploadDate,
+
+    /// The highest version number is the latest, so a hotfix of an older version uploaded later
+    /// does not count as latest. Versions with the same number are ordered by upload date and
+    /// versions which are no version numbers order last.
+    Semantic,
+}
+
+impl FromStr for VersionOrdering {
+    type Err = Error;
+
+    /// Parses the name of an ordering as given on the command line.
+    ///
+    /// ```
+    /// use recrep::model::VersionOrdering;
+    ///
+    /// assert_eq!("semantic".parse::<VersionOrdering>().unwrap(), VersionOrdering::Semantic);
+    /// assert!("alphabetic".parse::<VersionOrdering>().is_err());
+    /// ```
+    fn from_str(ordering: &str) -> Result<VersionOrdering> {
+        match ordering {
+            "upload-date" => Ok(VersionOrdering::UploadDate),
+            "semantic" => Ok(VersionOrdering::Semantic),
+            _ => Err(Error::InvalidOptions(
+                "The version ordering must be either `upload-date` or `semantic`.",
+            )),
+        }
+    }
+}
+
 pub struct VersionList {}
 
 impl VersionList {
-    pub fn latest_version(versions: Vec<Version>) -> Option<Version> {
-        VersionList::sort_versions(versions).first().cloned()
+    pub fn latest_version(versions: Vec<Version>, ordering: VersionOrdering) -> Option<Version> {
+        VersionList::sort_versions(versions, ordering)
+            .first()
+            .cloned()
     }
 
     pub fn latest_version_of_distribution_group(
         versions: Vec<Version>,
         distribution_group: String,
+        ordering: VersionOrdering,
     ) -> Option<Version> {
-        let sorted_versions = VersionList::sort_versions(versions);
-
-        let group_version = sorted_versions
-            .iter()
-            .filter(|version| {
-                VersionList::by_distribution_group(
-                    &distribution_group,
-                    version.distribution_groups.as_ref(),
-                )
-            })
-            .next();
+        let sorted_versions = VersionList::sort_versions(versions, ordering);
+
+        let group_version = sorted_versions.iter().find(|version| {
+            VersionList::by_distribution_group(
+                &distribution_group,
+                version.distribution_groups.as_ref(),
+            )
+        });
+
+        group_version.cloned()
+    }
 
-        return group_version.cloned();
+    /// The most recent versions, newest first, optionally restricted to a distribution group.
+    /// Versions released several times only appear once, at their most recent release.
+    pub fn latest_versions(
+        versions: Vec<Version>,
+        count: usize,
+        distribution_group: Option<String>,
+        ordering: VersionOrdering,
+    ) -> Vec<Version> {
+        let mut latest_versions: Vec<Version> = Vec::new();
+        for version in VersionList::sort_versions(versions, ordering) {
+            if latest_versions.len() == count {
+                break;
+            }
+            if let Some(group) = &distribution_group {
+                if !VersionList::by_distribution_group(group, version.distribution_groups.as_ref())
+                {
+                    continue;
+                }
+            }
+            if latest_versions
+                .iter()
+                .all(|latest| latest.short_version != version.short_version)
+            {
+                latest_versions.push(version);
+            }
+        }
+        latest_versions
+    }
+
+    /// The versions uploaded at or after the given point in time, latest first, optionally
+    /// restricted to a distribution group. Versions uploaded several times only appear once.
+    pub fn versions_uploaded_since(
+        versions: Vec<Version>,
+        since: DateTime<Utc>,
+        distribution_group: Option<String>,
+        ordering: VersionOrdering,
+    ) -> Vec<Version> {
+        let recent_versions: Vec<Version> = versions
+            .into_iter()
+            .filter(|version| version.uploaded_at >= since)
+            .collect();
+        let count = recent_versions.len();
+        VersionList::latest_versions(recent_versions, count, distribution_group, ordering)
+    }
+
+    /// All uploads of the version, each carrying its build number, from the most recent to the
+    /// oldest upload.
+    pub fn builds_of_version(versions: Vec<Version>, short_version: &str) -> Vec<Version> {
+        let builds = versions
+            .into_iter()
+            .filter(|version| version.short_version == short_version)
+            .collect();
+        VersionList::sort_versions(builds, VersionOrdering::UploadDate)
     }
 
     fn by_distribution_group(
@@ -30,15 +125,23 @@ impl VersionList {
         distribution_groups: Option<&Vec<DistributionGroup>>,
     ) -> bool {
         match distribution_groups {
-            Some(groups) => groups
-                .iter()
-                .any(|group| group.name == distribution_group.to_string()),
+            Some(groups) => groups.iter().any(|group| &group.name == distribution_group),
             None => false,
         }
     }
 
-    fn sort_versions(mut versions: Vec<Version>) -> Vec<Version> {
-        versions.sort_by(|a, b| b.uploaded_at.cmp(&a.uploaded_at));
+    /// Sorts the versions from the latest to the oldest.
+    fn sort_versions(mut versions: Vec<Version>, ordering: VersionOrdering) -> Vec<Version> {
+        match ordering {
+            VersionOrdering::UploadDate => {
+                versions.sort_by_key(|version| Reverse(version.uploaded_at))
+            }
+            VersionOrdering::Semantic => versions.sort_by(|a, b| {
+                b.semantic_version()
+                    .cmp(&a.semantic_version())
+                    .then(b.uploaded_at.cmp(&a.uploaded_at))
+            }),
+        }
         versions
     }
 }
@@ -51,26 +154,29 @@ mod tests {
     fn correct_sorting_of_version_list() {
         let version1 = Version {
             short_version: String::from("1.0"),
-            uploaded_at: String::from("2019-11-16T22:29:48.000Z"),
+            build: None,
+            uploaded_at: "2019-11-16T22:29:48.000Z".parse().unwrap(),
             distribution_groups: None,
         };
         let version2 = Version {
             short_version: String::from("1.0"),
-            uploaded_at: String::from("2019-11-17T22:29:48.000Z"),
+            build: None,
+            uploaded_at: "2019-11-17T22:29:48.000Z".parse().unwrap(),
             distribution_groups: None,
         };
         let version3 = Version {
             short_version: String::from("1.0"),
-            uploaded_at: String::from("2019-11-18T22:29:48.000Z"),
+            build: None,
+            uploaded_at: "2019-11-18T22:29:48.000Z".parse().unwrap(),
             distribution_groups: None,
         };
 
         let vec = vec![version1, version2, version3];
 
-        let latest = VersionList::latest_version(vec);
+        let latest = VersionList::latest_version(vec, VersionOrdering::UploadDate);
         match latest {
             Some(latest) => {
-                assert_eq!(latest.uploaded_at, String::from("2019-11-18T22:29:48.000Z"))
+                assert_eq!(latest.uploaded_at.to_rfc3339(), "2019-11-18T22:29:48+00:00")
             }
             None => panic!("There was no latest version in the returned sorted list"),
         }
@@ -94,18 +200,21 @@ mod tests {
 
         let version1 = Version {
             short_version: String::from("1.0"),
-            uploaded_at: String::from("2019-11-16T22:29:48.000Z"),
+            build: None,
+            uploaded_at: "2019-11-16T22:29:48.000Z".parse().unwrap(),
             distribution_groups: Some(vec![irrelevant_group.clone()]),
         };
 
         let version2 = Version {
             short_version: String::from(expected_version_string),
-            uploaded_at: String::from("2019-11-17T22:29:48.000Z"),
+            build: None,
+            uploaded_at: "2019-11-17T22:29:48.000Z".parse().unwrap(),
             distribution_groups: Some(vec![relevant_group]),
         };
         let version3 = Version {
             short_version: String::from("1.2"),
-            uploaded_at: String::from("2019-11-18T22:29:48.000Z"),
+            build: None,
+            uploaded_at: "2019-11-18T22:29:48.000Z".parse().unwrap(),
             distribution_groups: Some(vec![irrelevant_group.clone()]),
         };
 
@@ -114,6 +223,7 @@ mod tests {
         let found_version = VersionList::latest_version_of_distribution_group(
             vec,
             distribution_group_name.to_string(),
+            VersionOrdering::UploadDate,
         );
 
         match found_version {
@@ -125,6 +235,98 @@ mod tests {
         }
     }
 
+    #[test]
+    fn latest_versions_are_unique_and_limited() {
+        let version = |short_version: &str, uploaded_at: &str| Version {
+            short_version: String::from(short_version),
+            build: None,
+            uploaded_at: uploaded_at.parse().unwrap(),
+            distribution_groups: None,
+        };
+        let vec = vec![
+            version("1.0", "2019-11-16T22:29:48.000Z"),
+            version("1.1", "2019-11-17T22:29:48.000Z"),
+            version("1.1", "2019-11-18T22:29:48.000Z"),
+            version("1.2", "2019-11-19T22:29:48.000Z"),
+        ];
+
+        let latest_versions: Vec<String> =
+            VersionList::latest_versions(vec, 2, None, VersionOrdering::UploadDate)
+                .into_iter()
+                .map(|version| version.short_version)
+                .collect();
+
+        assert_eq!(latest_versions, vec!["1.2", "1.1"]);
+    }
+
+    #[test]
+    fn versions_uploaded_since_a_date() {
+        let version = |short_version: &str, uploaded_at: &str| Version {
+            short_version: String::from(short_version),
+            build: None,
+            uploaded_at: uploaded_at.parse().unwrap(),
+            distribution_groups: None,
+        };
+        let vec = vec![
+            version("1.0", "2019-11-16T22:29:48.000Z"),
+            version("1.1", "2019-11-17T22:29:48.000Z"),
+            version("1.2", "2019-11-18T22:29:48.000Z"),
+            version("1.1", "2019-11-19T22:29:48.000Z"),
+        ];
+
+        let versions: Vec<String> = VersionList::versions_uploaded_since(
+            vec,
+            "2019-11-17T00:00:00Z".parse().unwrap(),
+            None,
+            VersionOrdering::UploadDate,
+        )
+        .into_iter()
+        .map(|version| version.short_version)
+        .collect();
+
+        assert_eq!(versions, vec!["1.1", "1.2"]);
+    }
+
+    #[test]
+    fn semantic_ordering_ignores_later_uploaded_hotfixes() {
+        let version = |short_version: &str, uploaded_at: &str| Version {
+            short_version: String::from(short_version),
+            build: None,
+            uploaded_at: uploaded_at.parse().unwrap(),
+            distribution_groups: None,
+        };
+        let vec = vec![
+            version("7.41.0", "2019-11-16T22:29:48.000Z"),
+            version("7.40.1", "2019-11-17T22:29:48.000Z"),
+            version("nightly", "2019-11-18T22:29:48.000Z"),
+            version("7.41.0", "2019-11-15T22:29:48.000Z"),
+        ];
+
+        let latest_by_upload =
+            VersionList::latest_version(vec.clone(), VersionOrdering::UploadDate);
+        assert_eq!(latest_by_upload.unwrap().short_version, "nightly");
+
+        let sorted: Vec<String> = VersionList::sort_versions(vec, VersionOrdering::Semantic)
+            .into_iter()
+            .map(|version| {
+                format!(
+                    "{} {}",
+                    version.short_version,
+                    version.uploaded_at.date_naive()
+                )
+            })
+            .collect();
+        assert_eq!(
+            sorted,
+            vec![
+                "7.41.0 2019-11-16",
+                "7.41.0 2019-11-15",
+                "7.40.1 2019-11-17",
+                "nightly 2019-11-18"
+            ]
+        );
+    }
+
     #[test]
     fn dont_find_version_when_filtering_by_distribution_group() {
         let distribution_group_name = "Test distribution group";
@@ -137,18 +339,21 @@ mod tests {
 
         let version1 = Version {
             short_version: String::from("1.0"),
-            uploaded_at: String::from("2019-11-16T22:29:48.000Z"),
+            build: None,
+            uploaded_at: "2019-11-16T22:29:48.000Z".parse().unwrap(),
             distribution_groups: Some(vec![group.clone()]),
         };
 
         let version2 = Version {
             short_version: String::from("1.1"),
-            uploaded_at: String::from("2019-11-17T22:29:48.000Z"),
+            build: None,
+            uploaded_at: "2019-11-17T22:29:48.000Z".parse().unwrap(),
             distribution_groups: Some(vec![group.clone()]),
         };
         let version3 = Version {
             short_version: String::from("1.2"),
-            uploaded_at: String::from("2019-11-18T22:29:48.000Z"),
+            build: None,
+            uploaded_at: "2019-11-18T22:29:48.000Z".parse().unwrap(),
             distribution_groups: Some(vec![group.clone()]),
         };
 
@@ -157,13 +362,11 @@ mod tests {
         let found_version = VersionList::latest_version_of_distribution_group(
             vec,
             distribution_group_name.to_string(),
+            VersionOrdering::UploadDate,
         );
 
-        match found_version {
-            Some(_found_version) => {
-                panic!("Should not find any version");
-            }
-            None => (),
+        if found_version.is_some() {
+            panic!("Should not find any version");
         }
     }
 }
@@ -0,0 +1,412 @@
+use chrono::{DateTime, Utc};
+use handlebars::{
+    Context, Handlebars, Helper, HelperDef, HelperResult, JsonRender, Output, RenderContext,
+    RenderError,
+};
+use serde_json::Value;
+use std::fmt::Write;
+
+/// Registers the helpers all templates can use to present the report data. Relative times are
+/// measured from the time the clock tells when rendering, links point to the app of the
+/// organization.
+pub fn register(
+    handlebars: &mut Handlebars,
+    organization: &str,
+    application: &str,
+    clock: fn() -> DateTime<Utc>,
+) {
+    handlebars.register_helper("format_date", Box::new(format_date));
+    handlebars.register_helper("relative_time", Box::new(RelativeTime { clock }));
+    handlebars.register_helper("percent", Box::new(percent));
+    handlebars.register_helper("pluralize", Box::new(pluralize));
+    handlebars.register_helper("truncate", Box::new(truncate));
+    handlebars.register_helper("table_cell", Box::new(table_cell));
+    handlebars.register_helper(
+        "appcenter_link",
+        Box::new(AppCenterLink {
+            organization: organization.to_string(),
+            application: application.to_string(),
+        }),
+    );
+}
+
+/// `{{format_date firstOccurrence "%d.%m.%Y"}}` formats an RFC 3339 timestamp using a chrono
+/// format string, defaulting to `%Y-%m-%d`.
+fn format_date(
+    h: &Helper,
+    r: &Handlebars,
+    _: &Context,
+    rc: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let time = match timestamp(h, "format_date")? {
+        Some(time) => time,
+        None => return Ok(()),
+    };
+    let format = param(h, 1).and_then(Value::as_str).unwrap_or("%Y-%m-%d");
+
+    let mut formatted = String::new();
+    write!(formatted, "{}", time.format(format)).map_err(|_| {
+        RenderError::new(format!("`format_date` can't format dates as `{}`.", format))
+    })?;
+    write_escaped(&formatted, r, rc, out)
+}
+
+/// `{{relative_time lastOccurrence}}` describes how long ago an RFC 3339 timestamp was, e.g.
+/// `3 days ago`.
+struct RelativeTime {
+    clock: fn() -> DateTime<Utc>,
+}
+
+impl HelperDef for RelativeTime {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars,
+        _: &'rc Context,
+        rc: &mut RenderContext<'reg>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let time = match timestamp(h, "relative_time")? {
+            Some(time) => time,
+            None => return Ok(()),
+        };
+        let seconds = ((self.clock)() - time).num_seconds();
+
+        let (amount, unit) = match seconds.abs() {
+            seconds if seconds < 60 => return write_escaped("just now", r, rc, out),
+            seconds if seconds < 60 * 60 => (seconds / 60, "minute"),
+            seconds if seconds < 24 * 60 * 60 => (seconds / (60 * 60), "hour"),
+            seconds if seconds < 30 * 24 * 60 * 60 => (seconds / (24 * 60 * 60), "day"),
+            seconds if seconds < 12 * 30 * 24 * 60 * 60 => (seconds / (30 * 24 * 60 * 60), "month"),
+            // the last days of a year are already 12 months
+            seconds => ((seconds / (365 * 24 * 60 * 60)).max(1), "year"),
+        };
+        let plural = if amount == 1 { "" } else { "s" };
+        let relative_time = if seconds > 0 {
+            format!("{} {}{} ago", amount, unit, plural)
+        } else {
+            format!("in {} {}{}", amount, unit, plural)
+        };
+        write_escaped(&relative_time, r, rc, out)
+    }
+}
+
+/// `{{percent statistics.crash_free_users}}` renders a percentage, e.g. `99.12%`.
+/// `{{percent count ../threshold}}` renders the share of the first number in the second one. The
+/// `decimals` hash sets the precision, defaulting to 2.
+fn percent(
+    h: &Helper,
+    r: &Handlebars,
+    _: &Context,
+    rc: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let value = match number(h, 0, "percent")? {
+        Some(value) => value,
+        None => return Ok(()),
+    };
+    let percentage = if h.param(1).is_some() {
+        match number(h, 1, "percent")? {
+            Some(whole) if whole != 0.0 => value / whole * 100.0,
+            _ => return Ok(()),
+        }
+    } else {
+        value
+    };
+    let decimals = h
+        .hash_get("decimals")
+        .and_then(|decimals| decimals.value().as_u64())
+        .unwrap_or(2) as usize;
+
+    write_escaped(&format!("{:.*}%", decimals, percentage), r, rc, out)
+}
+
+/// `{{pluralize count "crash" "crashes"}}` renders the count followed by the singular or the
+/// plural, e.g. `1 crash` or `15 crashes`. The plural defaults to the singular followed by `s`.
+fn pluralize(
+    h: &Helper,
+    r: &Handlebars,
+    _: &Context,
+    rc: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let count = match number(h, 0, "pluralize")? {
+        Some(count) => count,
+        None => return Ok(()),
+    };
+    let singular = param(h, 1).and_then(Value::as_str).ok_or_else(|| {
+        RenderError::new("`pluralize` expects a count followed by the singular, e.g. `{{pluralize count \"crash\"}}`.")
+    })?;
+    let word = if count == 1.0 {
+        singular.to_string()
+    } else {
+        param(h, 2)
+            .and_then(Value::as_str)
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("{}s", singular))
+    };
+
+    write_escaped(&format!("{} {}", count, word), r, rc, out)
+}
+
+/// `{{truncate exceptionMessage 40}}` shortens the text to at most 40 characters, ending
+/// shortened texts with `…`.
+fn truncate(
+    h: &Helper,
+    r: &Handlebars,
+    _: &Context,
+    rc: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let text = match param(h, 0) {
+        Some(text) => text.render(),
+        None => return Ok(()),
+    };
+    let length = param(h, 1)
+        .and_then(Value::as_u64)
+        .filter(|length| *length > 0)
+        .ok_or_else(|| {
+            RenderError::new("`truncate` expects a text followed by a length greater than 0.")
+        })? as usize;
+
+    let truncated = if text.chars().count() > length {
+        text.chars().take(length - 1).collect::<String>() + "…"
+    } else {
+        text
+    };
+    write_escaped(&truncated, r, rc, out)
+}
+
+/// `{{table_cell exceptionMethod}}` renders the text as cell of a Markdown table, escaping `|`
+/// and joining lines, so it can't break the table.
+fn table_cell(
+    h: &Helper,
+    r: &Handlebars,
+    _: &Context,
+    rc: &mut RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    let text = match param(h, 0) {
+        Some(text) => text.render(),
+        None => return Ok(()),
+    };
+    let cell = text
+        .replace('|', "\\|")
+        .replace("\r\n", " ")
+        .replace(['\n', '\r'], " ");
+    write_escaped(&cell, r, rc, out)
+}
+
+/// `{{appcenter_link errorGroupId}}` renders the AppCenter url of the error group.
+struct AppCenterLink {
+    organization: String,
+    application: String,
+}
+
+impl HelperDef for AppCenterLink {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'reg, 'rc>,
+        r: &'reg Handlebars,
+        _: &'rc Context,
+        rc: &mut RenderContext<'reg>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let error_group_id = param(h, 0).ok_or_else(|| {
+            RenderError::new("`appcenter_link` expects an error group id, e.g. `{{appcenter_link errorGroupId}}`.")
+        })?;
+        let link = format!(
+            "https://appcenter.ms/orgs/{}/apps/{}/crashes/errors/{}/overview",
+            self.organization,
+            self.application,
+            error_group_id.render()
+        );
+        write_escaped(&link, r, rc, out)
+    }
+}
+
+/// The value of the parameter at the index, if it is given and not null.
+fn param<'a>(h: &'a Helper, index: usize) -> Option<&'a Value> {
+    h.param(index)
+        .map(|param| param.value())
+        .filter(|value| !value.is_null())
+}
+
+/// The number at the index, if it is given. Fails for anything but numbers.
+fn number(h: &Helper, index: usize, helper: &str) -> Result<Option<f64>, RenderError> {
+    match param(h, index) {
+        None => Ok(None),
+        Some(value) => value.as_f64().map(Some).ok_or_else(|| {
+            RenderError::new(format!("`{}` expects a number, got `{}`.", helper, value))
+        }),
+    }
+}
+
+/// The RFC 3339 timestamp passed as first parameter, if it is given, e.g. `2019-05-16T20:26:39Z`.
+fn timestamp(h: &Helper, helper: &str) -> Result<Option<DateTime<Utc>>, RenderError> {
+    match param(h, 0) {
+        None => Ok(None),
+        Some(value) => value
+            .as_str()
+            .and_then(|time| DateTime::parse_from_rfc3339(time).ok())
+            .map(|time| Some(time.with_timezone(&Utc)))
+            .ok_or_else(|| {
+                RenderError::new(format!(
+                    "`{}` expects an RFC 3339 timestamp, got `{}`.",
+                    helper, value
+                ))
+            }),
+    }
+}
+
+/// Writes the output of a helper, escaped like any other `{{expression}}` of the template.
+fn write_escaped(
+    output: &str,
+    r: &Handlebars,
+    rc: &RenderContext,
+    out: &mut dyn Output,
+) -> HelperResult {
+    if rc.is_disable_escape() {
+        out.write(output)?;
+    } else {
+        out.write(&r.get_escape_fn()(output))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeDelta, TimeZone};
+    use std::sync::atomic::{AtomicI64, Ordering};
+
+    fn fixed_clock() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2019, 5, 20, 12, 0, 0).unwrap()
+    }
+
+    fn render(template: &str, data: &Value) -> Result<String, RenderError> {
+        let mut handlebars = Handlebars::new();
+        register(&mut handlebars, "org", "app", fixed_clock);
+        handlebars
+            .register_template_string("template", template)
+            .unwrap();
+        handlebars.render("template", data)
+    }
+
+    #[test]
+    fn dates_are_formatted_absolutely_and_relatively() {
+        let data = json!({ "firstOccurrence": "2019-05-16T20:26:39Z" });
+
+        assert_eq!(
+            render("{{format_date firstOccurrence}}", &data).unwrap(),
+            "2019-05-16"
+        );
+        assert_eq!(
+            render("{{format_date firstOccurrence \"%d.%m.%Y %H:%M\"}}", &data).unwrap(),
+            "16.05.2019 20:26"
+        );
+        assert_eq!(
+            render("{{relative_time firstOccurrence}}", &data).unwrap(),
+            "3 days ago"
+        );
+        assert_eq!(
+            render("{{relative_time lastOccurrence}}", &data).unwrap(),
+            ""
+        );
+        assert!(render("{{format_date \"yesterday\"}}", &data).is_err());
+    }
+
+    #[test]
+    fn relative_times_are_measured_when_rendering() {
+        static DAYS_PASSED: AtomicI64 = AtomicI64::new(0);
+        fn advancing_clock() -> DateTime<Utc> {
+            fixed_clock() + TimeDelta::days(DAYS_PASSED.fetch_add(1, Ordering::SeqCst))
+        }
+        let mut handlebars = Handlebars::new();
+        register(&mut handlebars, "org", "app", advancing_clock);
+        let data = json!({ "firstOccurrence": "2019-05-16T20:26:39Z" });
+
+        let template = "{{relative_time firstOccurrence}}";
+        assert_eq!(
+            handlebars.render_template(template, &data).unwrap(),
+            "3 days ago"
+        );
+        assert_eq!(
+            handlebars.render_template(template, &data).unwrap(),
+            "4 days ago"
+        );
+    }
+
+    #[test]
+    fn relative_times_switch_to_years_after_11_months() {
+        let relative_time =
+            |time: &str| render("{{relative_time time}}", &json!({ "time": time })).unwrap();
+
+        assert_eq!(relative_time("2018-06-10T12:00:00Z"), "11 months ago");
+        assert_eq!(relative_time("2018-05-25T12:00:00Z"), "1 year ago");
+        assert_eq!(relative_time("2017-05-20T12:00:00Z"), "2 years ago");
+        assert_eq!(relative_time("2019-06-20T12:00:00Z"), "in 1 month");
+    }
+
+    #[test]
+    fn numbers_are_rendered_as_percentages_and_counts() {
+        let data = json!({ "count": 15, "threshold": 12, "rate": 99.1234 });
+
+        assert_eq!(render("{{percent rate}}", &data).unwrap(), "99.12%");
+        assert_eq!(
+            render("{{percent count threshold decimals=0}}", &data).unwrap(),
+            "125%"
+        );
+        assert_eq!(
+            render("{{pluralize count \"crash\" \"crashes\"}}", &data).unwrap(),
+            "15 crashes"
+        );
+        assert_eq!(
+            render("{{pluralize 1 \"device\"}}", &data).unwrap(),
+            "1 device"
+        );
+        assert_eq!(
+            render("{{pluralize deviceCount \"device\"}}", &data).unwrap(),
+            ""
+        );
+    }
+
+    #[test]
+    fn texts_are_truncated_and_escaped() {
+        let data =
+            json!({ "message": "*** -[__NSArrayM insertObject:atIndex:]: object cannot be nil" });
+
+        assert_eq!(
+            render("{{truncate message 20}}", &data).unwrap(),
+            "*** -[__NSArrayM in…"
+        );
+        assert_eq!(
+            render("{{truncate \"<b>bold</b>\" 100}}", &data).unwrap(),
+            "&lt;b&gt;bold&lt;/b&gt;"
+        );
+        assert!(render("{{truncate message}}", &data).is_err());
+    }
+
+    #[test]
+    fn table_cells_escape_pipes_and_line_breaks() {
+        let data = json!({ "method": "a || b\nc" });
+
+        assert_eq!(
+            render("{{{(table_cell method)}}}", &data).unwrap(),
+            "a \\|\\| b c"
+        );
+        assert_eq!(render("{{table_cell missing}}", &data).unwrap(), "");
+    }
+
+    #[test]
+    fn links_point_to_the_error_group() {
+        let data = json!({ "errorGroupId": "92620314u" });
+
+        assert_eq!(
+            render("{{appcenter_link errorGroupId}}", &data).unwrap(),
+            "https://appcenter.ms/orgs/org/apps/app/crashes/errors/92620314u/overview"
+        );
+        assert!(render("{{appcenter_link missing}}", &data).is_err());
+    }
+}
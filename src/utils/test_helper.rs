@@ -10,17 +10,22 @@ pub struct TestHelper {}
 impl TestHelper {
     pub fn report_from_json(path: &str) -> Report {
         let crash_list = TestHelper::crash_list_from_json(path);
-        let report = Report::new("version".to_string(), crash_list);
-        report
+        Report::new("version".to_string(), crash_list)
     }
 
     pub fn crash_list_from_json(path: &str) -> CrashList {
-        let path = Path::new(path);
-        let mut file = File::open(&path).expect("Unable to open test fixture");
-        let mut crashes_json = String::new();
-        file.read_to_string(&mut crashes_json)
-            .expect("Failed to read fixture file into string");
+        let crashes_json = TestHelper::fixture(path);
         let crash_list = crash_parsing::crash_list_from_json(crashes_json.as_str()).unwrap();
         crash_list
     }
+
+    /// Reads the fixture at the given path into a string
+    pub fn fixture(path: &str) -> String {
+        let path = Path::new(path);
+        let mut file = File::open(path).expect("Unable to open test fixture");
+        let mut json = String::new();
+        file.read_to_string(&mut json)
+            .expect("Failed to read fixture file into string");
+        json
+    }
 }